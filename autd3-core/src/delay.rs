@@ -11,15 +11,11 @@
  *
  */
 
-use anyhow::Result;
-
-use itertools::Itertools;
-
-use autd3_driver::NUM_TRANS_IN_UNIT;
+use crate::error::AUTDInternalError;
 
 use crate::{
     geometry::{Geometry, Transducer},
-    interface::{DatagramBody, Empty, Filled, Sendable},
+    interface::{DatagramBody, NullHeader, Sendable},
 };
 
 pub struct ModDelay {
@@ -27,25 +23,22 @@ pub struct ModDelay {
 }
 
 impl<T: Transducer> DatagramBody<T> for ModDelay {
-    fn init(&mut self) -> Result<()> {
+    fn init(&mut self) -> Result<(), AUTDInternalError> {
         self.sent = false;
         Ok(())
     }
 
-    fn pack(&mut self, geometry: &Geometry<T>, tx: &mut autd3_driver::TxDatagram) -> Result<()> {
+    fn pack(
+        &mut self,
+        geometry: &Geometry<T>,
+        tx: &mut autd3_driver::TxDatagram,
+    ) -> Result<(), AUTDInternalError> {
         autd3_driver::null_body(tx);
         if DatagramBody::<T>::is_finished(self) {
             return Ok(());
         }
 
-        let delays: Vec<[u16; NUM_TRANS_IN_UNIT]> = geometry
-            .transducers()
-            .map(|tr| tr.mod_delay())
-            .chunks(NUM_TRANS_IN_UNIT)
-            .into_iter()
-            .map(|c| c.collect::<Vec<_>>())
-            .map(|v| v.try_into().unwrap())
-            .collect();
+        let delays = geometry.device_slots(|tr| tr.mod_delay(), 0);
 
         self.sent = true;
         autd3_driver::mod_delay(&delays, tx)?;
@@ -58,23 +51,19 @@ impl<T: Transducer> DatagramBody<T> for ModDelay {
 }
 
 impl<T: Transducer> Sendable<T> for ModDelay {
-    type H = Empty;
-    type B = Filled;
+    type H = NullHeader;
+    type B = Self;
 
-    fn init(&mut self) -> Result<()> {
-        DatagramBody::<T>::init(self)
+    fn operation(self) -> (Self::H, Self::B) {
+        (NullHeader::new(), self)
     }
+}
 
-    fn pack(
-        &mut self,
-        _msg_id: u8,
-        geometry: &Geometry<T>,
-        tx: &mut autd3_driver::TxDatagram,
-    ) -> Result<()> {
-        DatagramBody::<T>::pack(self, geometry, tx)
-    }
+impl<T: Transducer> Sendable<T> for &mut ModDelay {
+    type H = NullHeader;
+    type B = Self;
 
-    fn is_finished(&self) -> bool {
-        DatagramBody::<T>::is_finished(self)
+    fn operation(self) -> (Self::H, Self::B) {
+        (NullHeader::new(), self)
     }
 }
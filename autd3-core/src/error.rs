@@ -13,16 +13,52 @@
 
 use thiserror::Error;
 
-use autd3_driver::{MAX_CYCLE, NUM_TRANS_IN_UNIT};
+use autd3_driver::{CPUError, DriverError, FPGAError, MAX_CYCLE, NUM_TRANS_IN_UNIT};
 
 #[derive(Error, Debug)]
 pub enum AUTDInternalError {
+    #[error(transparent)]
+    CPU(#[from] CPUError),
+    #[error(transparent)]
+    FPGA(#[from] FPGAError),
+    /// Error specific to each link, such as SOEM or TwinCAT
+    #[error(transparent)]
+    Link(Box<dyn std::error::Error + Send + Sync>),
+    /// Error in calculating gain, such as holo solvers
+    #[error(transparent)]
+    Gain(Box<dyn std::error::Error + Send + Sync>),
     #[error("Link is closed.")]
     LinkClosed,
     #[error("{} device{} connected, but {} {} specified", a, if *a == 1 {" is"} else {"s are"}, b, if *b== 1 {"is"} else {"are"})]
     DeviceNumberNotCorrect { a: usize, b: usize },
     #[error("{} transducer{} specified, but {} is correct", a,if *a == 1 {" is"} else {"s are"}, NUM_TRANS_IN_UNIT)]
     TransducerNumberNotCorrect { a: usize },
+    #[error(
+        "{0} transducers are specified, but a device must have 1 to {} transducers",
+        NUM_TRANS_IN_UNIT
+    )]
+    TransducerNumberOutOfRange(usize),
     #[error("Maximum cycle is {} , but {0} is specified", MAX_CYCLE)]
     CycleOutOfRange(u16),
+    #[error("Device id ({0}) is specified, but only {1} AUTDs are connected.")]
+    GroupedOutOfRange(usize, usize),
+    #[error("Transducer id ({0}) is specified, but only {1} transducers exist.")]
+    TransducerOutOfRange(usize, usize),
+    #[error("PointSTM supports only AUTD3 layout, but device {0} has a custom layout")]
+    PointSTMLayoutNotSupported(usize),
+    #[error("PointSTM cannot apply calibrations, but transducers of device {0} are calibrated")]
+    PointSTMCalibrationNotSupported(usize),
+    #[error("PointSTM cannot disable transducers, but device {0} has disabled transducers")]
+    PointSTMDisabledNotSupported(usize),
+    #[error("LookAt rotation requires non-zero direction and up which are not parallel")]
+    InvalidLookAt,
+}
+
+impl From<DriverError> for AUTDInternalError {
+    fn from(e: DriverError) -> Self {
+        match e {
+            DriverError::CPU(e) => Self::CPU(e),
+            DriverError::FPGA(e) => Self::FPGA(e),
+        }
+    }
 }
@@ -15,11 +15,11 @@ use std::marker::PhantomData;
 
 use autd3_driver::{Drive, TxDatagram};
 
+use crate::error::AUTDInternalError;
 use crate::{
     geometry::{Geometry, Transducer},
     interface::DatagramBody,
 };
-use anyhow::Result;
 
 pub struct GainProps<T: Transducer> {
     pub built: bool,
@@ -56,8 +56,13 @@ impl<T: Transducer> GainProps<T> {
         T::pack_head(tx);
     }
 
-    pub fn pack_body(&mut self, tx: &mut TxDatagram) -> Result<()> {
-        T::pack_body(&mut self.phase_sent, &mut self.duty_sent, &self.drives, tx)
+    pub fn pack_body(
+        &mut self,
+        geometry: &Geometry<T>,
+        tx: &mut TxDatagram,
+    ) -> Result<(), AUTDInternalError> {
+        let drives = geometry.pack_drives(&self.drives);
+        T::pack_body(&mut self.phase_sent, &mut self.duty_sent, &drives, tx)
     }
 }
 
@@ -68,14 +73,14 @@ impl<T: Transducer> Default for GainProps<T> {
 }
 
 pub trait IGain<T: Transducer> {
-    fn calc(&mut self, geometry: &Geometry<T>) -> Result<()>;
+    fn calc(&mut self, geometry: &Geometry<T>) -> Result<(), AUTDInternalError>;
 }
 
 /// Gain contains amplitude and phase of each transducer in the AUTD.
 /// Note that the amplitude means duty ratio of Pulse Width Modulation, respectively.
 pub trait Gain<T: Transducer>: IGain<T> + DatagramBody<T> {
-    fn build(&mut self, geometry: &Geometry<T>) -> Result<()>;
-    fn rebuild(&mut self, geometry: &Geometry<T>) -> Result<()>;
+    fn build(&mut self, geometry: &Geometry<T>) -> Result<(), AUTDInternalError>;
+    fn rebuild(&mut self, geometry: &Geometry<T>) -> Result<(), AUTDInternalError>;
     fn drives(&self) -> &[Drive];
     fn take_drives(self) -> Vec<Drive>;
     fn built(&self) -> bool;
@@ -13,7 +13,7 @@
 
 use std::marker::PhantomData;
 
-use super::{Geometry, LegacyTransducer, NormalPhaseTransducer, NormalTransducer};
+use super::{Geometry, LegacyTransducer, LengthUnit, NormalPhaseTransducer, NormalTransducer};
 
 pub struct Normal;
 pub struct NormalPhase;
@@ -22,6 +22,7 @@ pub struct Legacy;
 pub struct GeometryBuilder<M> {
     attenuation: f64,
     sound_speed: f64,
+    length_unit: LengthUnit,
     _mode: PhantomData<M>,
 }
 
@@ -35,6 +36,14 @@ impl<M> GeometryBuilder<M> {
         self.sound_speed = sound_speed;
         self
     }
+
+    /// Set the unit of positions given to [Geometry::add_device], which is millimeter by default
+    ///
+    /// Only device positions are converted. Gains and STMs always take target positions in millimeter, so convert them with [Geometry::to_internal].
+    pub fn length_unit(mut self, length_unit: LengthUnit) -> Self {
+        self.length_unit = length_unit;
+        self
+    }
 }
 
 impl GeometryBuilder<Normal> {
@@ -42,6 +51,7 @@ impl GeometryBuilder<Normal> {
         Self {
             attenuation: 0.0,
             sound_speed: 340.0,
+            length_unit: LengthUnit::Millimeter,
             _mode: PhantomData,
         }
     }
@@ -55,7 +65,7 @@ impl GeometryBuilder<Normal> {
     }
 
     pub fn build(self) -> Geometry<NormalTransducer> {
-        Geometry::<NormalTransducer>::new(self.attenuation, self.sound_speed)
+        Geometry::<NormalTransducer>::new(self.attenuation, self.sound_speed, self.length_unit)
     }
 }
 
@@ -69,7 +79,7 @@ impl GeometryBuilder<Legacy> {
     }
 
     pub fn build(self) -> Geometry<LegacyTransducer> {
-        Geometry::<LegacyTransducer>::new(self.attenuation, self.sound_speed)
+        Geometry::<LegacyTransducer>::new(self.attenuation, self.sound_speed, self.length_unit)
     }
 }
 
@@ -83,7 +93,7 @@ impl GeometryBuilder<NormalPhase> {
     }
 
     pub fn build(self) -> Geometry<NormalPhaseTransducer> {
-        Geometry::<NormalPhaseTransducer>::new(self.attenuation, self.sound_speed)
+        Geometry::<NormalPhaseTransducer>::new(self.attenuation, self.sound_speed, self.length_unit)
     }
 }
 
@@ -0,0 +1,82 @@
+/*
+ * File: calibration.rs
+ * Project: geometry
+ * Created Date: 18/10/2026
+ * Author: Shun Suzuki
+ * -----
+ * Last Modified: 18/10/2026
+ * Modified By: Shun Suzuki (suzuki@hapis.k.u-tokyo.ac.jp)
+ * -----
+ * Copyright (c) 2026 Shun Suzuki. All rights reserved.
+ *
+ */
+
+use std::f64::consts::PI;
+
+use autd3_driver::Drive;
+
+/// Correction of a transducer applied when drives are packed
+///
+/// To compensate a measured phase delay `d` and a relative efficiency `e`, set `phase_offset` to `-d` and `amp_scale` to `e_min / e`.
+/// Calibrations cannot be applied to [PointSTM](crate::stm::PointSTM), whose drives are calculated on the devices, so it returns an error for calibrated devices.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Calibration {
+    /// Phase added to the drive in radian
+    pub phase_offset: f64,
+    /// Factor multiplied to the drive amplitude
+    pub amp_scale: f64,
+}
+
+impl Calibration {
+    pub fn new(phase_offset: f64, amp_scale: f64) -> Self {
+        Self {
+            phase_offset,
+            amp_scale,
+        }
+    }
+
+    pub fn is_identity(&self) -> bool {
+        self.phase_offset == 0. && self.amp_scale == 1.
+    }
+
+    /// Return the drive compensated by this calibration
+    ///
+    /// The amplitude is clamped to 1.
+    pub fn apply(&self, drive: &Drive) -> Drive {
+        Drive {
+            phase: drive.phase + self.phase_offset / (2.0 * PI),
+            amp: (drive.amp * self.amp_scale).clamp(0., 1.),
+            cycle: drive.cycle,
+        }
+    }
+}
+
+impl Default for Calibration {
+    fn default() -> Self {
+        Self::new(0., 1.)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn apply_offsets_phase_and_clamps_amp() {
+        let drive = Drive {
+            phase: 0.25,
+            amp: 0.8,
+            cycle: 4096,
+        };
+
+        let identity = Calibration::default().apply(&drive);
+        assert_eq!((identity.phase, identity.amp), (drive.phase, drive.amp));
+
+        let calibrated = Calibration::new(PI, 0.5).apply(&drive);
+        assert_eq!(calibrated.phase, 0.75);
+        assert_eq!(calibrated.amp, 0.4);
+        assert_eq!(calibrated.cycle, 4096);
+
+        assert_eq!(Calibration::new(0., 2.).apply(&drive).amp, 1.);
+    }
+}
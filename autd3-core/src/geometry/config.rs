@@ -0,0 +1,619 @@
+/*
+ * File: config.rs
+ * Project: geometry
+ * Created Date: 18/10/2026
+ * Author: Shun Suzuki
+ * -----
+ * Last Modified: 18/10/2026
+ * Modified By: Shun Suzuki (suzuki@hapis.k.u-tokyo.ac.jp)
+ * -----
+ * Copyright (c) 2026 Shun Suzuki. All rights reserved.
+ *
+ */
+
+use std::path::{Path, PathBuf};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::error::AUTDInternalError;
+
+use super::{
+    AUTD3Layout, Calibration, DeviceLayout, Geometry, GeometryBuilder, LegacyTransducer,
+    LengthUnit, Matrix3, NormalPhaseTransducer, NormalTransducer, Quaternion, Rotation, Transducer,
+    UnitQuaternion, Vector3,
+};
+
+#[derive(Error, Debug)]
+pub enum GeometryConfigError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    TomlDe(#[from] toml::de::Error),
+    #[error(transparent)]
+    TomlSer(#[from] toml::ser::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error("Unknown file format: {0}. Use .toml or .json")]
+    UnknownFormat(PathBuf),
+    #[error("Geometry is described in {found:?} mode, but {expected:?} mode is required")]
+    ModeMismatch {
+        expected: TransducerMode,
+        found: TransducerMode,
+    },
+    #[error(transparent)]
+    Geometry(#[from] AUTDInternalError),
+    #[error("Transducer {0} does not exist")]
+    UnknownTransducer(usize),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TransducerMode {
+    Legacy,
+    Normal,
+    NormalPhase,
+}
+
+/// Transducer which can be built from [GeometryConfig]
+pub trait ConfigTransducer: Transducer {
+    const MODE: TransducerMode;
+    fn build_geometry(
+        attenuation: f64,
+        sound_speed: f64,
+        length_unit: LengthUnit,
+    ) -> Geometry<Self>;
+}
+
+impl ConfigTransducer for LegacyTransducer {
+    const MODE: TransducerMode = TransducerMode::Legacy;
+
+    fn build_geometry(
+        attenuation: f64,
+        sound_speed: f64,
+        length_unit: LengthUnit,
+    ) -> Geometry<Self> {
+        GeometryBuilder::new()
+            .legacy_mode()
+            .attenuation(attenuation)
+            .sound_speed(sound_speed)
+            .length_unit(length_unit)
+            .build()
+    }
+}
+
+impl ConfigTransducer for NormalTransducer {
+    const MODE: TransducerMode = TransducerMode::Normal;
+
+    fn build_geometry(
+        attenuation: f64,
+        sound_speed: f64,
+        length_unit: LengthUnit,
+    ) -> Geometry<Self> {
+        GeometryBuilder::new()
+            .attenuation(attenuation)
+            .sound_speed(sound_speed)
+            .length_unit(length_unit)
+            .build()
+    }
+}
+
+impl ConfigTransducer for NormalPhaseTransducer {
+    const MODE: TransducerMode = TransducerMode::NormalPhase;
+
+    fn build_geometry(
+        attenuation: f64,
+        sound_speed: f64,
+        length_unit: LengthUnit,
+    ) -> Geometry<Self> {
+        GeometryBuilder::new()
+            .normal_phase_mode()
+            .attenuation(attenuation)
+            .sound_speed(sound_speed)
+            .length_unit(length_unit)
+            .build()
+    }
+}
+
+/// Rotation of a device, see [Rotation]
+///
+/// The kind of rotation is given by `type`, e.g., `{ type = "quaternion", w = 1.0, x = 0.0, y = 0.0, z = 0.0 }`.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RotationConfig {
+    /// ZYZ Euler angles in radian, same as [Geometry::add_device]
+    Euler {
+        angles: [f64; 3],
+    },
+    EulerXyz {
+        angles: [f64; 3],
+    },
+    EulerZyx {
+        angles: [f64; 3],
+    },
+    Quaternion {
+        w: f64,
+        x: f64,
+        y: f64,
+        z: f64,
+    },
+    /// Rotation matrix in row-major order
+    Matrix {
+        rows: [[f64; 3]; 3],
+    },
+    LookAt {
+        direction: [f64; 3],
+        up: [f64; 3],
+    },
+}
+
+impl From<RotationConfig> for Rotation {
+    fn from(config: RotationConfig) -> Self {
+        let v = |[x, y, z]: [f64; 3]| Vector3::new(x, y, z);
+        match config {
+            RotationConfig::Euler { angles } => Self::EulerZYZ(v(angles)),
+            RotationConfig::EulerXyz { angles } => Self::EulerXYZ(v(angles)),
+            RotationConfig::EulerZyx { angles } => Self::EulerZYX(v(angles)),
+            RotationConfig::Quaternion { w, x, y, z } => {
+                Self::Quaternion(UnitQuaternion::from_quaternion(Quaternion::new(w, x, y, z)))
+            }
+            RotationConfig::Matrix { rows } => {
+                Self::Matrix(Matrix3::from_row_slice(&rows.concat()))
+            }
+            RotationConfig::LookAt { direction, up } => Self::LookAt {
+                direction: v(direction),
+                up: v(up),
+            },
+        }
+    }
+}
+
+impl RotationConfig {
+    pub fn to_quaternion(&self) -> Result<UnitQuaternion, AUTDInternalError> {
+        Rotation::from(*self).to_quaternion()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DeviceConfig {
+    /// Global position in [GeometryConfig::length_unit]
+    pub position: [f64; 3],
+    /// Local positions of transducers in millimeter, see [DeviceLayout]. [AUTD3Layout] is used if omitted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub layout: Option<Vec<[f64; 3]>>,
+    // TOML requires tables to follow values, so this must be the last field
+    pub rotation: RotationConfig,
+}
+
+fn default_sound_speed() -> f64 {
+    340.0
+}
+
+/// Description of geometry which can be saved in TOML or JSON
+///
+/// # Example
+///
+/// ```
+/// use autd3_core::geometry::{GeometryConfig, LegacyTransducer, Vector3};
+///
+/// let config: GeometryConfig = toml::from_str(
+///     r#"
+///     mode = "legacy"
+///     sound_speed = 346.0
+///     length_unit = "meter"
+///
+///     [[devices]]
+///     position = [0.0, 0.0, 0.0]
+///     rotation = { type = "euler", angles = [0.0, 0.0, 0.0] }
+///
+///     [[devices]]
+///     position = [0.192, 0.0, 0.0]
+///     rotation = { type = "quaternion", w = 1.0, x = 0.0, y = 0.0, z = 0.0 }
+///
+///     [[devices]]
+///     position = [0.0, 0.0, 0.2]
+///     rotation = { type = "look_at", direction = [0.0, 0.0, -1.0], up = [0.0, 1.0, 0.0] }
+///     layout = [[0.0, 0.0, 0.0], [10.0, 0.0, 0.0], [20.0, 0.0, 0.0]]
+///     "#,
+/// )
+/// .unwrap();
+///
+/// let geometry = config.build::<LegacyTransducer>().unwrap();
+/// assert_eq!(geometry.num_devices(), 3);
+/// assert_eq!(geometry.num_transducers(), 249 * 2 + 3);
+/// assert_eq!(geometry.sound_speed(), 346.0);
+/// assert!((geometry.devices()[1].origin() - Vector3::new(192., 0., 0.)).norm() < 1e-9);
+///
+/// let exported = GeometryConfig::from_geometry(&geometry);
+/// assert_eq!(exported.devices.len(), 3);
+/// assert!(exported.devices[0].layout.is_none());
+/// assert_eq!(exported.devices[2].layout.as_ref().unwrap().len(), 3);
+/// assert_eq!(exported.build::<LegacyTransducer>().unwrap().num_devices(), 3);
+/// ```
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GeometryConfig {
+    pub mode: TransducerMode,
+    #[serde(default = "default_sound_speed")]
+    pub sound_speed: f64,
+    #[serde(default)]
+    pub attenuation: f64,
+    #[serde(default)]
+    pub length_unit: LengthUnit,
+    #[serde(default)]
+    pub devices: Vec<DeviceConfig>,
+}
+
+impl GeometryConfig {
+    /// Export the geometry. Rotations are written as quaternions.
+    pub fn from_geometry<T: ConfigTransducer>(geometry: &Geometry<T>) -> Self {
+        Self {
+            mode: T::MODE,
+            sound_speed: geometry.sound_speed,
+            attenuation: geometry.attenuation,
+            length_unit: geometry.length_unit(),
+            devices: geometry
+                .devices()
+                .iter()
+                .map(|dev| {
+                    let layout =
+                        (dev.local_positions() != AUTD3Layout.local_positions()).then(|| {
+                            dev.local_positions()
+                                .iter()
+                                .map(|p| [p.x, p.y, p.z])
+                                .collect()
+                        });
+                    let q = dev.rotation().quaternion();
+                    let p = geometry.from_internal(*dev.origin());
+                    DeviceConfig {
+                        position: [p.x, p.y, p.z],
+                        rotation: RotationConfig::Quaternion {
+                            w: q.w,
+                            x: q.i,
+                            y: q.j,
+                            z: q.k,
+                        },
+                        layout,
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    /// Build geometry through [GeometryBuilder]
+    ///
+    /// Return [GeometryConfigError::ModeMismatch] if the mode is different from that of `T`.
+    pub fn build<T: ConfigTransducer>(&self) -> Result<Geometry<T>, GeometryConfigError> {
+        if self.mode != T::MODE {
+            return Err(GeometryConfigError::ModeMismatch {
+                expected: T::MODE,
+                found: self.mode,
+            });
+        }
+        let mut geometry = T::build_geometry(self.attenuation, self.sound_speed, self.length_unit);
+        self.devices.iter().try_for_each(|dev| {
+            let [x, y, z] = dev.position;
+            let position = Vector3::new(x, y, z);
+            let rotation = Rotation::from(dev.rotation);
+            match &dev.layout {
+                Some(layout) => {
+                    let layout: Vec<_> = layout
+                        .iter()
+                        .map(|&[x, y, z]| Vector3::new(x, y, z))
+                        .collect();
+                    geometry.add_device_with_layout(position, rotation, &layout)
+                }
+                None => geometry.add_device_with_layout(position, rotation, &AUTD3Layout),
+            }
+        })?;
+        Ok(geometry)
+    }
+
+    /// Load from a file. The format is selected by the extension, `.toml` or `.json`.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, GeometryConfigError> {
+        Format::read(path.as_ref())
+    }
+
+    /// Save to a file. The format is selected by the extension, `.toml` or `.json`.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), GeometryConfigError> {
+        Format::write(path.as_ref(), self)
+    }
+}
+
+fn default_amp_scale() -> f64 {
+    1.0
+}
+
+/// Calibration of a transducer, see [Calibration]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TransducerCalibrationConfig {
+    /// Id of the transducer
+    pub id: usize,
+    /// Phase offset in radian
+    #[serde(default)]
+    pub phase_offset: f64,
+    #[serde(default = "default_amp_scale")]
+    pub amp_scale: f64,
+}
+
+/// Calibration table which can be saved in TOML or JSON
+///
+/// Transducers which are not listed are not calibrated.
+///
+/// # Example
+///
+/// ```
+/// use autd3_core::geometry::{CalibrationConfig, GeometryBuilder, Vector3};
+///
+/// let mut geometry = GeometryBuilder::new().build();
+/// geometry.add_device(Vector3::zeros(), Vector3::zeros());
+///
+/// let config: CalibrationConfig = toml::from_str(
+///     r#"
+///     [[transducers]]
+///     id = 3
+///     phase_offset = 0.1
+///     amp_scale = 0.8
+///
+///     [[transducers]]
+///     id = 10
+///     amp_scale = 0.9
+///     "#,
+/// )
+/// .unwrap();
+/// config.apply(&mut geometry).unwrap();
+///
+/// assert_eq!(geometry.calibration(3).unwrap().phase_offset, 0.1);
+/// assert_eq!(geometry.calibration(10).unwrap().amp_scale, 0.9);
+/// assert_eq!(CalibrationConfig::from_geometry(&geometry), config);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct CalibrationConfig {
+    #[serde(default)]
+    pub transducers: Vec<TransducerCalibrationConfig>,
+}
+
+impl CalibrationConfig {
+    /// Export calibrations of the geometry other than identity
+    pub fn from_geometry<T: Transducer>(geometry: &Geometry<T>) -> Self {
+        Self {
+            transducers: geometry
+                .devices()
+                .iter()
+                .flat_map(|dev| dev.transducers().iter().zip(dev.calibrations()))
+                .filter(|(_, c)| !c.is_identity())
+                .map(|(tr, c)| TransducerCalibrationConfig {
+                    id: tr.id(),
+                    phase_offset: c.phase_offset,
+                    amp_scale: c.amp_scale,
+                })
+                .collect(),
+        }
+    }
+
+    /// Set calibrations to the geometry
+    ///
+    /// Return [GeometryConfigError::UnknownTransducer] if the geometry does not have a listed transducer.
+    pub fn apply<T: Transducer>(
+        &self,
+        geometry: &mut Geometry<T>,
+    ) -> Result<(), GeometryConfigError> {
+        self.transducers.iter().try_for_each(|c| {
+            *geometry
+                .calibration_mut(c.id)
+                .ok_or(GeometryConfigError::UnknownTransducer(c.id))? =
+                Calibration::new(c.phase_offset, c.amp_scale);
+            Ok(())
+        })
+    }
+
+    /// Load from a file. The format is selected by the extension, `.toml` or `.json`.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, GeometryConfigError> {
+        Format::read(path.as_ref())
+    }
+
+    /// Save to a file. The format is selected by the extension, `.toml` or `.json`.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), GeometryConfigError> {
+        Format::write(path.as_ref(), self)
+    }
+}
+
+enum Format {
+    Toml,
+    Json,
+}
+
+impl Format {
+    fn of(path: &Path) -> Result<Self, GeometryConfigError> {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("toml") => Ok(Self::Toml),
+            Some("json") => Ok(Self::Json),
+            _ => Err(GeometryConfigError::UnknownFormat(path.to_path_buf())),
+        }
+    }
+
+    fn read<D: DeserializeOwned>(path: &Path) -> Result<D, GeometryConfigError> {
+        let format = Self::of(path)?;
+        let s = std::fs::read_to_string(path)?;
+        match format {
+            Self::Toml => Ok(toml::from_str(&s)?),
+            Self::Json => Ok(serde_json::from_str(&s)?),
+        }
+    }
+
+    fn write<S: Serialize>(path: &Path, value: &S) -> Result<(), GeometryConfigError> {
+        let s = match Self::of(path)? {
+            Self::Toml => toml::to_string_pretty(value)?,
+            Self::Json => serde_json::to_string_pretty(value)?,
+        };
+        std::fs::write(path, s)?;
+        Ok(())
+    }
+}
+
+impl<T: Transducer> Geometry<T> {
+    /// Load calibrations of transducers from a TOML or JSON file, see [CalibrationConfig]
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path to the file. The format is selected by the extension.
+    ///
+    pub fn load_calibration<P: AsRef<Path>>(&mut self, path: P) -> Result<(), GeometryConfigError> {
+        CalibrationConfig::load(path)?.apply(self)
+    }
+
+    /// Save calibrations of transducers to a TOML or JSON file
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path to the file. The format is selected by the extension.
+    ///
+    pub fn save_calibration<P: AsRef<Path>>(&self, path: P) -> Result<(), GeometryConfigError> {
+        CalibrationConfig::from_geometry(self).save(path)
+    }
+}
+
+impl<T: ConfigTransducer> Geometry<T> {
+    /// Load geometry from a TOML or JSON file
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path to the file. The format is selected by the extension.
+    ///
+    /// ```
+    /// use autd3_core::geometry::{Geometry, GeometryBuilder, NormalTransducer, Transducer, Vector3};
+    ///
+    /// let mut geometry = GeometryBuilder::new().build();
+    /// geometry.add_device(Vector3::zeros(), Vector3::zeros());
+    /// geometry.add_device(Vector3::new(0., 0., 200.), Vector3::new(0., std::f64::consts::PI, 0.));
+    ///
+    /// let path = std::env::temp_dir().join("autd3_geometry_doctest.json");
+    /// geometry.save(&path).unwrap();
+    /// let loaded = Geometry::<NormalTransducer>::load(&path).unwrap();
+    ///
+    /// assert_eq!(loaded.num_devices(), 2);
+    /// geometry
+    ///     .transducers()
+    ///     .zip(loaded.transducers())
+    ///     .for_each(|(a, b)| assert!((a.position() - b.position()).norm() < 1e-9));
+    /// ```
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, GeometryConfigError> {
+        GeometryConfig::load(path)?.build()
+    }
+
+    /// Save geometry to a TOML or JSON file
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path to the file. The format is selected by the extension.
+    ///
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), GeometryConfigError> {
+        GeometryConfig::from_geometry(self).save(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("autd3_config_test_{}_{}", std::process::id(), name))
+    }
+
+    #[test]
+    fn geometry_round_trips_through_toml() {
+        let mut geometry = GeometryBuilder::new()
+            .length_unit(LengthUnit::Meter)
+            .sound_speed(346.)
+            .build();
+        geometry.add_device(Vector3::zeros(), Vector3::zeros());
+        geometry
+            .add_device_with_layout(
+                Vector3::new(0., 0., 0.2),
+                Rotation::EulerXYZ(Vector3::new(0.1, 0.2, 0.3)),
+                &vec![Vector3::zeros(), Vector3::new(10., 0., 0.)],
+            )
+            .unwrap();
+
+        let path = temp_path("geometry.toml");
+        geometry.save(&path).unwrap();
+        let config = GeometryConfig::load(&path).unwrap();
+        let loaded = Geometry::<NormalTransducer>::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(config, GeometryConfig::from_geometry(&geometry));
+        assert_eq!(loaded.num_transducers(), geometry.num_transducers());
+        geometry
+            .transducers()
+            .zip(loaded.transducers())
+            .for_each(|(a, b)| assert!((a.position() - b.position()).norm() < 1e-9));
+    }
+
+    #[test]
+    fn every_rotation_round_trips_through_toml() {
+        let rotations = [
+            RotationConfig::Euler {
+                angles: [0.1, 0.2, 0.3],
+            },
+            RotationConfig::EulerXyz {
+                angles: [0.1, 0.2, 0.3],
+            },
+            RotationConfig::EulerZyx {
+                angles: [0.1, 0.2, 0.3],
+            },
+            RotationConfig::Quaternion {
+                w: 1.,
+                x: 0.,
+                y: 0.,
+                z: 0.,
+            },
+            RotationConfig::Matrix {
+                rows: [[1., 0., 0.], [0., 1., 0.], [0., 0., 1.]],
+            },
+            RotationConfig::LookAt {
+                direction: [0., 0., -1.],
+                up: [0., 1., 0.],
+            },
+        ];
+        let config = GeometryConfig {
+            mode: TransducerMode::Legacy,
+            sound_speed: 340.,
+            attenuation: 0.,
+            length_unit: LengthUnit::Millimeter,
+            devices: rotations
+                .iter()
+                .map(|&rotation| DeviceConfig {
+                    position: [0., 0., 0.],
+                    rotation,
+                    layout: None,
+                })
+                .collect(),
+        };
+
+        let s = toml::to_string_pretty(&config).unwrap();
+        assert_eq!(toml::from_str::<GeometryConfig>(&s).unwrap(), config);
+    }
+
+    #[test]
+    fn calibration_round_trips_through_toml() {
+        let mut geometry = GeometryBuilder::new().build();
+        geometry.add_device(Vector3::zeros(), Vector3::zeros());
+        *geometry.calibration_mut(3).unwrap() = Calibration::new(0.1, 0.8);
+        *geometry.calibration_mut(10).unwrap() = Calibration::new(0., 0.9);
+
+        let path = temp_path("calibration.toml");
+        geometry.save_calibration(&path).unwrap();
+        let mut loaded = GeometryBuilder::new().build();
+        loaded.add_device(Vector3::zeros(), Vector3::zeros());
+        loaded.load_calibration(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(
+            CalibrationConfig::from_geometry(&loaded),
+            CalibrationConfig::from_geometry(&geometry)
+        );
+        assert_eq!(
+            CalibrationConfig::from_geometry(&loaded).transducers.len(),
+            2
+        );
+    }
+}
@@ -11,15 +11,24 @@
  *
  */
 
-use autd3_driver::{
-    is_missing_transducer, NUM_TRANS_IN_UNIT, NUM_TRANS_X, NUM_TRANS_Y, TRANS_SPACING_MM,
-};
+use autd3_driver::NUM_TRANS_IN_UNIT;
+
+use crate::error::AUTDInternalError;
 
-use super::{Matrix3, Matrix4, Quaternion, Transducer, UnitQuaternion, Vector3, Vector4};
+use super::{
+    AUTD3Layout, Calibration, DeviceLayout, Matrix3, Matrix4, Quaternion, Transducer,
+    UnitQuaternion, Vector3, Vector4,
+};
 
 pub struct Device<T: Transducer> {
     transducers: Vec<T>,
+    local_positions: Vec<Vector3>,
+    is_autd3_layout: bool,
+    calibrations: Vec<Calibration>,
+    enabled: bool,
+    transducer_enabled: Vec<bool>,
     origin: Vector3,
+    rotation: UnitQuaternion,
     trans_inv: Matrix3,
 }
 
@@ -33,14 +42,117 @@ impl<T: Transducer> Device<T> {
         self.trans_inv * (global_position - self.origin)
     }
 
+    /// Return the global position of the device
+    pub fn origin(&self) -> &Vector3 {
+        &self.origin
+    }
+
+    pub fn rotation(&self) -> &UnitQuaternion {
+        &self.rotation
+    }
+
     pub fn transducers(&self) -> &[T] {
         &self.transducers
     }
 
+    /// Return positions of transducers in the local coordinate of the device, given by [DeviceLayout]
+    pub fn local_positions(&self) -> &[Vector3] {
+        &self.local_positions
+    }
+
+    /// Return true if the transducers are arranged in [AUTD3Layout]
+    pub fn is_autd3_layout(&self) -> bool {
+        self.is_autd3_layout
+    }
+
     pub fn transducers_mut(&mut self) -> &mut [T] {
         &mut self.transducers
     }
 
+    /// Return calibrations of transducers in the same order as [Device::transducers]
+    pub fn calibrations(&self) -> &[Calibration] {
+        &self.calibrations
+    }
+
+    pub fn calibrations_mut(&mut self) -> &mut [Calibration] {
+        &mut self.calibrations
+    }
+
+    /// Return true if any transducer has a calibration other than identity
+    pub fn is_calibrated(&self) -> bool {
+        self.calibrations.iter().any(|c| !c.is_identity())
+    }
+
+    /// Enable or disable the device
+    ///
+    /// All transducers of a disabled device are driven with zero duty, and holo gains do not use them.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Enable or disable a transducer
+    ///
+    /// # Arguments
+    ///
+    /// * `idx` - Index of the transducer in the device
+    /// * `enabled` - Whether the transducer is enabled
+    ///
+    pub fn set_transducer_enabled(
+        &mut self,
+        idx: usize,
+        enabled: bool,
+    ) -> Result<(), AUTDInternalError> {
+        let num_transducers = self.transducer_enabled.len();
+        let e =
+            self.transducer_enabled
+                .get_mut(idx)
+                .ok_or(AUTDInternalError::TransducerOutOfRange(
+                    idx,
+                    num_transducers,
+                ))?;
+        *e = enabled;
+        Ok(())
+    }
+
+    /// Return true if both the device and the transducer are enabled
+    ///
+    /// False is returned if the transducer does not exist.
+    ///
+    /// # Arguments
+    ///
+    /// * `idx` - Index of the transducer in the device
+    ///
+    pub fn is_transducer_enabled(&self, idx: usize) -> bool {
+        self.enabled && self.transducer_enabled.get(idx).is_some_and(|&e| e)
+    }
+
+    /// Return transducers which are enabled
+    pub fn enabled_transducers(&self) -> impl Iterator<Item = &T> {
+        self.transducers
+            .iter()
+            .zip(self.transducer_enabled.iter())
+            .filter(|_| self.enabled)
+            .filter(|(_, &e)| e)
+            .map(|(tr, _)| tr)
+    }
+
+    /// Copy calibrations and enabled flags from the device of the same layout
+    pub(crate) fn copy_settings_from<U: Transducer>(&mut self, src: &Device<U>) {
+        self.calibrations.copy_from_slice(&src.calibrations);
+        self.enabled = src.enabled;
+        self.transducer_enabled
+            .copy_from_slice(&src.transducer_enabled);
+    }
+
+    /// Return true if the device or any of its transducers is disabled
+    pub fn has_disabled(&self) -> bool {
+        !self.enabled || self.transducer_enabled.iter().any(|&e| !e)
+    }
+
     pub fn center(&self) -> Vector3 {
         let sum: Vector3 = self.transducers().iter().map(|t| t.position()).sum();
         sum / self.transducers.len() as f64
@@ -48,25 +160,44 @@ impl<T: Transducer> Device<T> {
 }
 
 impl<T: Transducer> Device<T> {
+    /// Create device with [AUTD3Layout]
     pub fn new(id: usize, position: Vector3, rotation: UnitQuaternion) -> Self {
+        Self::with_layout(id * NUM_TRANS_IN_UNIT, position, rotation, &AUTD3Layout)
+            .expect("AUTD3 layout always fits in a device")
+    }
+
+    /// Create device with the layout
+    ///
+    /// # Arguments
+    ///
+    /// * `first_id` - Id of the first transducer of the device
+    /// * `position` - Global position of the origin of the layout
+    /// * `rotation` - Rotation of the device
+    /// * `layout` - Layout of transducers
+    ///
+    pub fn with_layout<D: DeviceLayout + ?Sized>(
+        first_id: usize,
+        position: Vector3,
+        rotation: UnitQuaternion,
+        layout: &D,
+    ) -> Result<Self, AUTDInternalError> {
+        let local_positions = layout.local_positions();
+        if local_positions.is_empty() || local_positions.len() > NUM_TRANS_IN_UNIT {
+            return Err(AUTDInternalError::TransducerNumberOutOfRange(
+                local_positions.len(),
+            ));
+        }
+
         let rot_mat: Matrix4 = From::from(rotation);
         let trans_mat = rot_mat.append_translation(&position);
         let x_direction = Self::get_direction(Vector3::x(), rotation);
         let y_direction = Self::get_direction(Vector3::y(), rotation);
         let z_direction = Self::get_direction(Vector3::z(), rotation);
 
-        let transducers: Vec<T> = itertools::iproduct!((0..NUM_TRANS_Y), (0..NUM_TRANS_X))
-            .filter(|&(y, x)| !is_missing_transducer(x, y))
-            .map(|(y, x)| {
-                Vector4::new(
-                    x as f64 * TRANS_SPACING_MM,
-                    y as f64 * TRANS_SPACING_MM,
-                    0.,
-                    1.,
-                )
-            })
-            .map(|p| trans_mat * p)
-            .zip(id * NUM_TRANS_IN_UNIT..)
+        let transducers: Vec<T> = local_positions
+            .iter()
+            .map(|p| trans_mat * Vector4::new(p.x, p.y, p.z, 1.))
+            .zip(first_id..)
             .map(|(p, i)| {
                 T::new(
                     i,
@@ -78,13 +209,58 @@ impl<T: Transducer> Device<T> {
             })
             .collect();
 
-        let origin = *transducers[0].position();
         let trans_inv = Matrix3::from_columns(&[x_direction, y_direction, z_direction]).transpose();
+        let is_autd3_layout = local_positions == AUTD3Layout.local_positions();
 
-        Self {
+        Ok(Self {
+            calibrations: vec![Calibration::default(); transducers.len()],
+            enabled: true,
+            transducer_enabled: vec![true; transducers.len()],
             transducers,
-            origin,
+            local_positions,
+            is_autd3_layout,
+            origin: position,
+            rotation,
             trans_inv,
-        }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::LegacyTransducer;
+
+    fn device() -> Device<LegacyTransducer> {
+        Device::new(0, Vector3::zeros(), UnitQuaternion::identity())
+    }
+
+    #[test]
+    fn set_transducer_enabled_checks_index() {
+        let mut dev = device();
+        assert!(dev.set_transducer_enabled(248, false).is_ok());
+        assert!(!dev.is_transducer_enabled(248));
+        assert!(matches!(
+            dev.set_transducer_enabled(249, false),
+            Err(AUTDInternalError::TransducerOutOfRange(249, 249))
+        ));
+        assert!(!dev.is_transducer_enabled(249));
+    }
+
+    #[test]
+    fn disabled_device_disables_all_transducers() {
+        let mut dev = device();
+        dev.set_transducer_enabled(0, false).unwrap();
+        assert!(dev.has_disabled());
+        assert_eq!(dev.enabled_transducers().count(), 248);
+        assert_eq!(dev.enabled_transducers().next().unwrap().id(), 1);
+
+        dev.set_enabled(false);
+        assert_eq!(dev.enabled_transducers().count(), 0);
+        assert!(!dev.is_transducer_enabled(1));
+
+        dev.set_enabled(true);
+        dev.set_transducer_enabled(0, true).unwrap();
+        assert!(!dev.has_disabled());
     }
 }
@@ -0,0 +1,125 @@
+/*
+ * File: layout.rs
+ * Project: geometry
+ * Created Date: 18/10/2026
+ * Author: Shun Suzuki
+ * -----
+ * Last Modified: 18/10/2026
+ * Modified By: Shun Suzuki (suzuki@hapis.k.u-tokyo.ac.jp)
+ * -----
+ * Copyright (c) 2026 Shun Suzuki. All rights reserved.
+ *
+ */
+
+use autd3_driver::{is_missing_transducer, NUM_TRANS_X, NUM_TRANS_Y, TRANS_SPACING_MM};
+
+use super::Vector3;
+
+/// Arrangement of transducers on a device
+///
+/// The `i`-th transducer is driven by the `i`-th slot of the frame, so a layout can have at most [NUM_TRANS_IN_UNIT](crate::NUM_TRANS_IN_UNIT) transducers.
+pub trait DeviceLayout {
+    /// Return positions of transducers in millimeter in the local coordinate of the device
+    fn local_positions(&self) -> Vec<Vector3>;
+}
+
+/// Layout of AUTD3 board, which is 18x14 grid without three transducers for screws
+#[derive(Clone, Copy, Debug, Default)]
+pub struct AUTD3Layout;
+
+impl DeviceLayout for AUTD3Layout {
+    fn local_positions(&self) -> Vec<Vector3> {
+        GridLayout::new(NUM_TRANS_X, NUM_TRANS_Y, TRANS_SPACING_MM)
+            .with_missing((0..NUM_TRANS_Y).flat_map(|y| {
+                (0..NUM_TRANS_X)
+                    .filter(move |&x| is_missing_transducer(x, y))
+                    .map(move |x| (x, y))
+            }))
+            .local_positions()
+    }
+}
+
+/// Rectangular grid layout
+///
+/// Transducers are ordered from the first row, in which `x` increases.
+#[derive(Clone, Debug)]
+pub struct GridLayout {
+    nx: usize,
+    ny: usize,
+    pitch: f64,
+    missing: Vec<(usize, usize)>,
+}
+
+impl GridLayout {
+    /// constructor
+    ///
+    /// # Arguments
+    ///
+    /// * `nx` - Number of columns
+    /// * `ny` - Number of rows
+    /// * `pitch` - Distance between adjacent transducers in millimeter
+    ///
+    pub fn new(nx: usize, ny: usize, pitch: f64) -> Self {
+        Self {
+            nx,
+            ny,
+            pitch,
+            missing: vec![],
+        }
+    }
+
+    /// Remove transducers at the grid positions `(x, y)`
+    pub fn with_missing<I: IntoIterator<Item = (usize, usize)>>(mut self, missing: I) -> Self {
+        self.missing.extend(missing);
+        self
+    }
+}
+
+impl DeviceLayout for GridLayout {
+    fn local_positions(&self) -> Vec<Vector3> {
+        itertools::iproduct!((0..self.ny), (0..self.nx))
+            .filter(|&(y, x)| !self.missing.contains(&(x, y)))
+            .map(|(y, x)| Vector3::new(x as f64 * self.pitch, y as f64 * self.pitch, 0.))
+            .collect()
+    }
+}
+
+/// Arbitrary layout, such as circular arrays
+impl DeviceLayout for Vec<Vector3> {
+    fn local_positions(&self) -> Vec<Vector3> {
+        self.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn autd3_layout_skips_screw_holes() {
+        let positions = AUTD3Layout.local_positions();
+        assert_eq!(positions.len(), 249);
+        assert_eq!(positions[18], Vector3::new(0., TRANS_SPACING_MM, 0.));
+        assert_eq!(
+            positions[19],
+            Vector3::new(3. * TRANS_SPACING_MM, TRANS_SPACING_MM, 0.)
+        );
+    }
+
+    #[test]
+    fn grid_layout_is_row_major() {
+        let positions = GridLayout::new(3, 2, 5.)
+            .with_missing([(1, 0)])
+            .local_positions();
+        assert_eq!(
+            positions,
+            vec![
+                Vector3::new(0., 0., 0.),
+                Vector3::new(10., 0., 0.),
+                Vector3::new(0., 5., 0.),
+                Vector3::new(5., 5., 0.),
+                Vector3::new(10., 5., 0.),
+            ]
+        );
+    }
+}
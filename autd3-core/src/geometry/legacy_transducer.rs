@@ -15,6 +15,8 @@ use std::f64::consts::PI;
 
 use autd3_driver::Drive;
 
+use crate::error::AUTDInternalError;
+
 use super::{Transducer, Vector3};
 
 pub struct LegacyTransducer {
@@ -102,7 +104,7 @@ impl Transducer for LegacyTransducer {
         duty_sent: &mut bool,
         drives: &[Drive],
         tx: &mut autd3_driver::TxDatagram,
-    ) -> anyhow::Result<()> {
+    ) -> Result<(), AUTDInternalError> {
         autd3_driver::normal_legacy_body(drives, tx)?;
         *phase_sent = true;
         *duty_sent = true;
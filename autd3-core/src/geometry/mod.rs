@@ -12,11 +12,16 @@
  */
 
 mod builder;
+mod calibration;
+#[cfg(feature = "serde")]
+mod config;
 mod device;
+mod layout;
 mod legacy_transducer;
 mod normal_phase_transducer;
 mod normal_transducer;
 mod transducer;
+mod transform;
 
 pub type Vector3 = nalgebra::Vector3<f64>;
 pub type Vector4 = nalgebra::Vector4<f64>;
@@ -25,27 +30,38 @@ pub type UnitQuaternion = nalgebra::UnitQuaternion<f64>;
 pub type Matrix3 = nalgebra::Matrix3<f64>;
 pub type Matrix4 = nalgebra::Matrix4<f64>;
 
-use autd3_driver::NUM_TRANS_IN_UNIT;
+use std::borrow::Cow;
+
+use autd3_driver::{Drive, NUM_TRANS_IN_UNIT};
 pub use builder::*;
+pub use calibration::*;
+#[cfg(feature = "serde")]
+pub use config::*;
 pub use device::*;
+pub use layout::*;
 pub use legacy_transducer::*;
 pub use normal_phase_transducer::*;
 pub use normal_transducer::*;
 pub use transducer::*;
+pub use transform::*;
+
+use crate::error::AUTDInternalError;
 
 #[derive(Default)]
 pub struct Geometry<T: Transducer> {
     devices: Vec<Device<T>>,
     pub attenuation: f64,
     pub sound_speed: f64,
+    length_unit: LengthUnit,
 }
 
 impl<T: Transducer> Geometry<T> {
-    fn new(attenuation: f64, sound_speed: f64) -> Geometry<T> {
+    fn new(attenuation: f64, sound_speed: f64, length_unit: LengthUnit) -> Geometry<T> {
         Geometry {
             devices: vec![],
             attenuation,
             sound_speed,
+            length_unit,
         }
     }
 
@@ -54,7 +70,7 @@ impl<T: Transducer> Geometry<T> {
     }
 
     pub fn num_transducers(&self) -> usize {
-        self.devices.len() * NUM_TRANS_IN_UNIT
+        self.devices.iter().map(|dev| dev.transducers().len()).sum()
     }
 
     pub fn devices(&self) -> &[Device<T>] {
@@ -87,6 +103,23 @@ impl<T: Transducer> Geometry<T> {
     pub fn set_sound_speed(&mut self, sound_speed: f64) {
         self.sound_speed = sound_speed;
     }
+
+    /// Return the unit of positions given to [Geometry::add_device]
+    pub fn length_unit(&self) -> LengthUnit {
+        self.length_unit
+    }
+
+    /// Convert a position in [Geometry::length_unit] into millimeter, which gains and STMs use
+    ///
+    /// Target positions of gains and STMs, e.g., the focal point of `Focus` or points of [PointSTM](crate::stm::PointSTM), are not converted automatically, so pass them through this method.
+    pub fn to_internal(&self, position: Vector3) -> Vector3 {
+        self.length_unit.to_millimeter(position)
+    }
+
+    /// Convert a position in millimeter into [Geometry::length_unit]
+    pub fn from_internal(&self, position: Vector3) -> Vector3 {
+        self.length_unit.from_millimeter(position)
+    }
 }
 
 impl Geometry<LegacyTransducer> {
@@ -108,25 +141,92 @@ impl<T: Transducer> Geometry<T> {
     ///
     /// # Arguments
     ///
-    /// * `pos` - Global position of AUTD.
-    /// * `rot` - ZYZ Euler angles.
+    /// * `pos` - Global position of AUTD in [Geometry::length_unit].
+    /// * `rot` - Rotation of AUTD. [Vector3] is regarded as ZYZ Euler angles.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `rot` is an invalid [Rotation::LookAt]. Use [add_device_with_layout](#method.add_device_with_layout) to handle it as an error.
     ///
     /// # Example
     ///
     /// ```
     /// use std::f64::consts::PI;
-    /// use autd3_core::geometry::{Vector3, GeometryBuilder};
+    /// use autd3_core::geometry::{GeometryBuilder, LengthUnit, Rotation, Transducer, Vector3};
     ///
     /// let mut geometry = GeometryBuilder::new().build();
     ///
     /// geometry.add_device(Vector3::zeros(), Vector3::zeros());
     /// geometry.add_device(Vector3::new(192., 0., 0.), Vector3::new(-PI, 0., 0.));
+    ///
+    /// let mut geometry = GeometryBuilder::new().length_unit(LengthUnit::Meter).build();
+    /// geometry.add_device(Vector3::new(0., 0., 0.2), Rotation::EulerXYZ(Vector3::new(PI, 0., 0.)));
+    /// geometry.add_device(
+    ///     Vector3::new(0., 0.2, 0.),
+    ///     Rotation::LookAt { direction: Vector3::y(), up: Vector3::z() },
+    /// );
+    ///
+    /// let dev = &geometry.devices()[0];
+    /// assert!((dev.origin() - Vector3::new(0., 0., 200.)).norm() < 1e-9);
+    /// assert!((dev.transducers()[0].z_direction() + Vector3::z()).norm() < 1e-9);
+    /// assert!((geometry.devices()[1].transducers()[0].z_direction() - Vector3::y()).norm() < 1e-9);
     /// ```
-    pub fn add_device(&mut self, position: Vector3, euler_angles: Vector3) {
-        let q = UnitQuaternion::from_axis_angle(&Vector3::z_axis(), euler_angles.x)
-            * UnitQuaternion::from_axis_angle(&Vector3::y_axis(), euler_angles.y)
-            * UnitQuaternion::from_axis_angle(&Vector3::z_axis(), euler_angles.z);
-        self.add_device_quaternion(position, q)
+    pub fn add_device<R: Into<Rotation>>(&mut self, position: Vector3, rotation: R) {
+        // AUTD3 layout always fits in a device, so only the rotation can be invalid
+        if let Err(e) = self.add_device_with_layout(position, rotation, &AUTD3Layout) {
+            panic!("{}", e)
+        }
+    }
+
+    /// Add device with a custom layout to the geometry.
+    ///
+    /// # Arguments
+    ///
+    /// * `pos` - Global position of the origin of the layout in [Geometry::length_unit].
+    /// * `rot` - Rotation of the device.
+    /// * `layout` - Layout of transducers, which must have 1 to [NUM_TRANS_IN_UNIT] transducers.
+    ///
+    /// Return an error if the layout has too many or no transducers, or `rot` is an invalid [Rotation::LookAt].
+    ///
+    /// Note that the firmware calculates [PointSTM](crate::stm::PointSTM) assuming [AUTD3Layout], so it cannot be sent to devices with other layouts.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::f64::consts::PI;
+    /// use autd3_core::geometry::{GeometryBuilder, GridLayout, Vector3};
+    ///
+    /// let mut geometry = GeometryBuilder::new().build();
+    /// geometry.add_device(Vector3::zeros(), Vector3::zeros());
+    ///
+    /// let ring: Vec<_> = (0..60)
+    ///     .map(|i| 2. * PI * i as f64 / 60.)
+    ///     .map(|t| Vector3::new(50. * t.cos(), 50. * t.sin(), 0.))
+    ///     .collect();
+    /// geometry.add_device_with_layout(Vector3::new(0., 0., 300.), Vector3::zeros(), &ring).unwrap();
+    ///
+    /// let grid = GridLayout::new(15, 15, 8.).with_missing([(0, 0), (14, 14)]);
+    /// geometry.add_device_with_layout(Vector3::new(200., 0., 0.), Vector3::zeros(), &grid).unwrap();
+    ///
+    /// assert_eq!(geometry.num_transducers(), 249 + 60 + 223);
+    /// assert!(geometry
+    ///     .add_device_with_layout(Vector3::zeros(), Vector3::zeros(), &GridLayout::new(16, 16, 8.))
+    ///     .is_err());
+    /// ```
+    pub fn add_device_with_layout<R: Into<Rotation>, D: DeviceLayout + ?Sized>(
+        &mut self,
+        position: Vector3,
+        rotation: R,
+        layout: &D,
+    ) -> Result<(), AUTDInternalError> {
+        let device = Device::<T>::with_layout(
+            self.num_transducers(),
+            self.to_internal(position),
+            rotation.into().to_quaternion()?,
+            layout,
+        )?;
+        self.devices.push(device);
+        Ok(())
     }
 
     /// Add device to the geometry.
@@ -136,11 +236,248 @@ impl<T: Transducer> Geometry<T> {
     ///
     /// # Arguments
     ///
-    /// * `pos` - Global position of AUTD.
+    /// * `pos` - Global position of AUTD in [Geometry::length_unit].
     /// * `rot` - Rotation quaternion.
     ///
     pub fn add_device_quaternion(&mut self, position: Vector3, rotation: UnitQuaternion) {
-        let id = self.devices.len();
-        self.devices.push(Device::<T>::new(id, position, rotation));
+        self.add_device(position, rotation)
+    }
+
+    /// Return geometry with the same devices and another transducer type
+    ///
+    /// Transducer specific settings such as cycles are reset to the default, while calibrations and disabled elements are kept.
+    pub fn with_transducer<U: Transducer>(&self) -> Geometry<U> {
+        let mut geometry = Geometry::new(self.attenuation, self.sound_speed, self.length_unit);
+        self.devices.iter().for_each(|dev| {
+            geometry
+                .add_device_with_layout(
+                    self.from_internal(*dev.origin()),
+                    *dev.rotation(),
+                    &dev.local_positions().to_vec(),
+                )
+                .expect("layout of existing device always fits")
+        });
+        geometry
+            .devices
+            .iter_mut()
+            .zip(self.devices.iter())
+            .for_each(|(dst, src)| dst.copy_settings_from(src));
+        geometry
+    }
+
+    /// Place values of transducers into the slots of the frames
+    ///
+    /// Values of the `i`-th device are placed from the `i * NUM_TRANS_IN_UNIT`-th slot, and the slots without transducers are filled with `empty`.
+    ///
+    /// # Arguments
+    ///
+    /// * `values` - Values indexed by transducer id
+    /// * `empty` - Value for the slots without transducers
+    ///
+    pub fn to_slots<'a, U: Clone>(&self, values: &'a [U], empty: U) -> Cow<'a, [U]> {
+        if self
+            .devices
+            .iter()
+            .all(|dev| dev.transducers().len() == NUM_TRANS_IN_UNIT)
+        {
+            return Cow::Borrowed(values);
+        }
+        let mut slots = vec![empty; self.num_devices() * NUM_TRANS_IN_UNIT];
+        self.devices.iter().enumerate().for_each(|(i, dev)| {
+            dev.transducers().iter().enumerate().for_each(|(j, tr)| {
+                slots[i * NUM_TRANS_IN_UNIT + j] = values[tr.id()].clone();
+            })
+        });
+        Cow::Owned(slots)
+    }
+
+    /// Return the index of the device and the index in the device of the transducer
+    fn locate(&self, id: usize) -> Option<(usize, usize)> {
+        self.devices.iter().enumerate().find_map(|(i, dev)| {
+            let first = dev.transducers().first()?.id();
+            let j = id.checked_sub(first)?;
+            (j < dev.transducers().len()).then_some((i, j))
+        })
+    }
+
+    /// Return the calibration of the transducer
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - Id of the transducer
+    ///
+    pub fn calibration(&self, id: usize) -> Option<&Calibration> {
+        let (i, j) = self.locate(id)?;
+        Some(&self.devices[i].calibrations()[j])
+    }
+
+    /// Return the mutable calibration of the transducer
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - Id of the transducer
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::f64::consts::PI;
+    /// use autd3_core::{geometry::{Calibration, GeometryBuilder, Vector3}, Drive};
+    ///
+    /// let mut geometry = GeometryBuilder::new().legacy_mode().build();
+    /// geometry.add_device(Vector3::zeros(), Vector3::zeros());
+    ///
+    /// *geometry.calibration_mut(1).unwrap() = Calibration::new(PI, 0.5);
+    ///
+    /// let drives = vec![Drive { phase: 0., amp: 1., cycle: 4096 }; geometry.num_transducers()];
+    /// let packed = geometry.pack_drives(&drives);
+    /// assert_eq!(packed[0].amp, 1.);
+    /// assert_eq!(packed[1].amp, 0.5);
+    /// assert_eq!(packed[1].phase, 0.5);
+    /// ```
+    pub fn calibration_mut(&mut self, id: usize) -> Option<&mut Calibration> {
+        let (i, j) = self.locate(id)?;
+        Some(&mut self.devices[i].calibrations_mut()[j])
+    }
+
+    /// Disable the transducer, which is driven with zero duty and is not used by holo gains
+    ///
+    /// [PointSTM](crate::stm::PointSTM) cannot be sent while any transducer is disabled.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - Id of the transducer
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use autd3_core::{geometry::{GeometryBuilder, Vector3}, Drive};
+    ///
+    /// let mut geometry = GeometryBuilder::new().legacy_mode().build();
+    /// geometry.add_device(Vector3::zeros(), Vector3::zeros());
+    /// geometry.add_device(Vector3::new(192., 0., 0.), Vector3::zeros());
+    ///
+    /// geometry.disable_transducer(3).unwrap();
+    /// geometry.devices_mut()[1].set_enabled(false);
+    /// assert_eq!(geometry.enabled_transducers().count(), 248);
+    /// assert!(geometry.disable_transducer(498).is_err());
+    ///
+    /// let drives = vec![Drive { phase: 0., amp: 1., cycle: 4096 }; geometry.num_transducers()];
+    /// let packed = geometry.pack_drives(&drives);
+    /// assert_eq!(packed[2].amp, 1.);
+    /// assert_eq!(packed[3].amp, 0.);
+    /// assert!(packed[249..].iter().all(|d| d.amp == 0.));
+    /// ```
+    pub fn disable_transducer(&mut self, id: usize) -> Result<(), AUTDInternalError> {
+        self.set_transducer_enabled(id, false)
+    }
+
+    /// Enable the transducer disabled by [Geometry::disable_transducer]
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - Id of the transducer
+    ///
+    pub fn enable_transducer(&mut self, id: usize) -> Result<(), AUTDInternalError> {
+        self.set_transducer_enabled(id, true)
+    }
+
+    fn set_transducer_enabled(
+        &mut self,
+        id: usize,
+        enabled: bool,
+    ) -> Result<(), AUTDInternalError> {
+        let (i, j) = self
+            .locate(id)
+            .ok_or_else(|| AUTDInternalError::TransducerOutOfRange(id, self.num_transducers()))?;
+        self.devices[i].set_transducer_enabled(j, enabled)
+    }
+
+    /// Return true if the transducer and its device are enabled
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - Id of the transducer
+    ///
+    pub fn is_transducer_enabled(&self, id: usize) -> bool {
+        self.locate(id)
+            .is_some_and(|(i, j)| self.devices[i].is_transducer_enabled(j))
+    }
+
+    /// Return transducers which are enabled, in order of id
+    pub fn enabled_transducers(&self) -> impl Iterator<Item = &T> {
+        self.devices
+            .iter()
+            .flat_map(|dev| dev.enabled_transducers())
+    }
+
+    pub fn num_enabled_transducers(&self) -> usize {
+        self.enabled_transducers().count()
+    }
+
+    /// Place drives into the slots of the frames, which is called by gains and STMs just before packing
+    ///
+    /// Calibrations of transducers are applied here, and disabled transducers are driven with zero duty.
+    ///
+    /// # Arguments
+    ///
+    /// * `drives` - Drives indexed by transducer id
+    ///
+    pub fn pack_drives<'a>(&self, drives: &'a [Drive]) -> Cow<'a, [Drive]> {
+        let empty = Drive {
+            phase: 0.,
+            amp: 0.,
+            cycle: 4096,
+        };
+        if !self
+            .devices
+            .iter()
+            .any(|dev| dev.is_calibrated() || dev.has_disabled())
+        {
+            return self.to_slots(drives, empty);
+        }
+        let packed: Vec<Drive> = self
+            .devices
+            .iter()
+            .flat_map(|dev| {
+                dev.transducers()
+                    .iter()
+                    .zip(dev.calibrations())
+                    .enumerate()
+                    .map(move |(j, (tr, c))| {
+                        let d = c.apply(&drives[tr.id()]);
+                        if dev.is_transducer_enabled(j) {
+                            d
+                        } else {
+                            Drive { amp: 0., ..d }
+                        }
+                    })
+            })
+            .collect();
+        Cow::Owned(self.to_slots(&packed, empty).into_owned())
+    }
+
+    /// Return a property of transducers for each device in the layout of the frames
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - Property of a transducer
+    /// * `empty` - Value for the slots without transducers
+    ///
+    pub fn device_slots<U: Copy, F: Fn(&T) -> U>(
+        &self,
+        f: F,
+        empty: U,
+    ) -> Vec<[U; NUM_TRANS_IN_UNIT]> {
+        self.devices
+            .iter()
+            .map(|dev| {
+                let mut slots = [empty; NUM_TRANS_IN_UNIT];
+                dev.transducers()
+                    .iter()
+                    .zip(slots.iter_mut())
+                    .for_each(|(tr, s)| *s = f(tr));
+                slots
+            })
+            .collect()
     }
 }
@@ -13,13 +13,11 @@
 
 use std::f64::consts::PI;
 
-use anyhow::Result;
-
 use autd3_driver::{Drive, FPGA_CLK_FREQ, MAX_CYCLE};
 
 use crate::{
     error::AUTDInternalError,
-    interface::{DatagramBody, Empty, Filled, Sendable},
+    interface::{DatagramBody, NullHeader, Sendable},
 };
 
 use super::{Geometry, Transducer, Vector3};
@@ -102,7 +100,7 @@ impl Transducer for NormalPhaseTransducer {
         duty_sent: &mut bool,
         drives: &[Drive],
         tx: &mut autd3_driver::TxDatagram,
-    ) -> anyhow::Result<()> {
+    ) -> Result<(), AUTDInternalError> {
         autd3_driver::normal_phase_body(drives, tx)?;
         *phase_sent = true;
         *duty_sent = true;
@@ -119,15 +117,15 @@ impl Transducer for NormalPhaseTransducer {
 }
 
 impl NormalPhaseTransducer {
-    pub fn set_cycle(&mut self, cycle: u16) -> Result<()> {
+    pub fn set_cycle(&mut self, cycle: u16) -> Result<(), AUTDInternalError> {
         if cycle > MAX_CYCLE {
-            return Err(AUTDInternalError::CycleOutOfRange(cycle).into());
+            return Err(AUTDInternalError::CycleOutOfRange(cycle));
         }
         self.cycle = cycle;
         Ok(())
     }
 
-    pub fn set_frequency(&mut self, freq: f64) -> Result<()> {
+    pub fn set_frequency(&mut self, freq: f64) -> Result<(), AUTDInternalError> {
         let cycle = (FPGA_CLK_FREQ as f64 / freq).round() as u16;
         self.set_cycle(cycle)
     }
@@ -159,22 +157,22 @@ impl Amplitudes {
 }
 
 impl DatagramBody<NormalPhaseTransducer> for Amplitudes {
-    fn init(&mut self) -> Result<()> {
+    fn init(&mut self) -> Result<(), AUTDInternalError> {
         self.sent = false;
         Ok(())
     }
 
     fn pack(
         &mut self,
-        _geometry: &Geometry<NormalPhaseTransducer>,
+        geometry: &Geometry<NormalPhaseTransducer>,
         tx: &mut autd3_driver::TxDatagram,
-    ) -> Result<()> {
+    ) -> Result<(), AUTDInternalError> {
         autd3_driver::normal_head(tx);
         if DatagramBody::<NormalPhaseTransducer>::is_finished(self) {
             return Ok(());
         }
         self.sent = true;
-        autd3_driver::normal_duty_body(&self.drives, tx)?;
+        autd3_driver::normal_duty_body(&geometry.pack_drives(&self.drives), tx)?;
         Ok(())
     }
 
@@ -184,23 +182,19 @@ impl DatagramBody<NormalPhaseTransducer> for Amplitudes {
 }
 
 impl Sendable<NormalPhaseTransducer> for Amplitudes {
-    type H = Empty;
-    type B = Filled;
+    type H = NullHeader;
+    type B = Self;
 
-    fn init(&mut self) -> Result<()> {
-        DatagramBody::<NormalPhaseTransducer>::init(self)
+    fn operation(self) -> (Self::H, Self::B) {
+        (NullHeader::new(), self)
     }
+}
 
-    fn pack(
-        &mut self,
-        _msg_id: u8,
-        geometry: &Geometry<NormalPhaseTransducer>,
-        tx: &mut autd3_driver::TxDatagram,
-    ) -> Result<()> {
-        DatagramBody::<NormalPhaseTransducer>::pack(self, geometry, tx)
-    }
+impl Sendable<NormalPhaseTransducer> for &mut Amplitudes {
+    type H = NullHeader;
+    type B = Self;
 
-    fn is_finished(&self) -> bool {
-        DatagramBody::<NormalPhaseTransducer>::is_finished(self)
+    fn operation(self) -> (Self::H, Self::B) {
+        (NullHeader::new(), self)
     }
 }
@@ -13,8 +13,6 @@
 
 use std::f64::consts::PI;
 
-use anyhow::{Ok, Result};
-
 use autd3_driver::{Drive, FPGA_CLK_FREQ, MAX_CYCLE};
 
 use crate::error::AUTDInternalError;
@@ -91,7 +89,7 @@ impl Transducer for NormalTransducer {
         duty_sent: &mut bool,
         drives: &[Drive],
         tx: &mut autd3_driver::TxDatagram,
-    ) -> anyhow::Result<()> {
+    ) -> Result<(), AUTDInternalError> {
         if !*phase_sent {
             autd3_driver::normal_phase_body(drives, tx)?;
             *phase_sent = true;
@@ -120,15 +118,15 @@ impl Transducer for NormalTransducer {
 }
 
 impl NormalTransducer {
-    pub fn set_cycle(&mut self, cycle: u16) -> Result<()> {
+    pub fn set_cycle(&mut self, cycle: u16) -> Result<(), AUTDInternalError> {
         if cycle > MAX_CYCLE {
-            return Err(AUTDInternalError::CycleOutOfRange(cycle).into());
+            return Err(AUTDInternalError::CycleOutOfRange(cycle));
         }
         self.cycle = cycle;
         Ok(())
     }
 
-    pub fn set_frequency(&mut self, freq: f64) -> Result<()> {
+    pub fn set_frequency(&mut self, freq: f64) -> Result<(), AUTDInternalError> {
         let cycle = (FPGA_CLK_FREQ as f64 / freq).round() as u16;
         self.set_cycle(cycle)
     }
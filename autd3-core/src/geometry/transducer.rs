@@ -11,7 +11,7 @@
  *
  */
 
-use anyhow::Result;
+use crate::error::AUTDInternalError;
 
 use autd3_driver::{Drive, TxDatagram};
 
@@ -43,5 +43,5 @@ pub trait Transducer: Sized {
         duty_sent: &mut bool,
         drives: &[Drive],
         tx: &mut TxDatagram,
-    ) -> Result<()>;
+    ) -> Result<(), AUTDInternalError>;
 }
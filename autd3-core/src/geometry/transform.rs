@@ -0,0 +1,139 @@
+/*
+ * File: transform.rs
+ * Project: geometry
+ * Created Date: 18/10/2026
+ * Author: Shun Suzuki
+ * -----
+ * Last Modified: 18/10/2026
+ * Modified By: Shun Suzuki (suzuki@hapis.k.u-tokyo.ac.jp)
+ * -----
+ * Copyright (c) 2026 Shun Suzuki. All rights reserved.
+ *
+ */
+
+use crate::error::AUTDInternalError;
+
+use super::{Matrix3, UnitQuaternion, Vector3};
+
+/// Rotation of a device
+///
+/// Euler angles are intrinsic and in radian. The angles are applied in order of the name, e.g., `EulerXYZ(a)` rotates around x-axis by `a.x`, then around the new y-axis by `a.y`, and then around the new z-axis by `a.z`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Rotation {
+    EulerXYZ(Vector3),
+    EulerZYX(Vector3),
+    EulerZYZ(Vector3),
+    Quaternion(UnitQuaternion),
+    /// Rotation matrix whose columns are x, y and z axes of the device
+    Matrix(Matrix3),
+    /// Rotation which makes z-axis of the device face `direction`, with y-axis as close as possible to `up`
+    ///
+    /// `direction` and `up` must be non-zero and not parallel to each other.
+    LookAt {
+        direction: Vector3,
+        up: Vector3,
+    },
+}
+
+impl Rotation {
+    pub fn to_quaternion(&self) -> Result<UnitQuaternion, AUTDInternalError> {
+        let rx = |a| UnitQuaternion::from_axis_angle(&Vector3::x_axis(), a);
+        let ry = |a| UnitQuaternion::from_axis_angle(&Vector3::y_axis(), a);
+        let rz = |a| UnitQuaternion::from_axis_angle(&Vector3::z_axis(), a);
+        Ok(match *self {
+            Self::EulerXYZ(a) => rx(a.x) * ry(a.y) * rz(a.z),
+            Self::EulerZYX(a) => rz(a.x) * ry(a.y) * rx(a.z),
+            Self::EulerZYZ(a) => rz(a.x) * ry(a.y) * rz(a.z),
+            Self::Quaternion(q) => q,
+            Self::Matrix(m) => UnitQuaternion::from_matrix(&m),
+            Self::LookAt { direction, up } => {
+                // face_towards yields NaN if the x-axis, i.e., up x direction, is undefined
+                if direction.cross(&up).norm() <= f64::EPSILON * direction.norm() * up.norm() {
+                    return Err(AUTDInternalError::InvalidLookAt);
+                }
+                UnitQuaternion::face_towards(&direction, &up)
+            }
+        })
+    }
+}
+
+/// ZYZ Euler angles, for compatibility with [Geometry::add_device](super::Geometry::add_device)
+impl From<Vector3> for Rotation {
+    fn from(euler_angles: Vector3) -> Self {
+        Self::EulerZYZ(euler_angles)
+    }
+}
+
+impl From<UnitQuaternion> for Rotation {
+    fn from(q: UnitQuaternion) -> Self {
+        Self::Quaternion(q)
+    }
+}
+
+/// Unit of lengths given to [Geometry](super::Geometry)
+///
+/// Positions are stored in millimeter internally, and gains and STMs always work in millimeter.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum LengthUnit {
+    #[default]
+    Millimeter,
+    Centimeter,
+    Meter,
+}
+
+impl LengthUnit {
+    /// Scale factor to convert a length in this unit into millimeter
+    pub fn scale(&self) -> f64 {
+        match self {
+            Self::Millimeter => 1.,
+            Self::Centimeter => 10.,
+            Self::Meter => 1000.,
+        }
+    }
+
+    /// Convert a position in this unit into millimeter
+    pub fn to_millimeter(&self, v: Vector3) -> Vector3 {
+        v * self.scale()
+    }
+
+    /// Convert a position in millimeter into this unit
+    pub fn from_millimeter(&self, v: Vector3) -> Vector3 {
+        v / self.scale()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn look_at() {
+        let q = Rotation::LookAt {
+            direction: Vector3::y(),
+            up: Vector3::z(),
+        }
+        .to_quaternion()
+        .unwrap();
+        assert!((q * Vector3::z() - Vector3::y()).norm() < 1e-9);
+        assert!((q * Vector3::y() - Vector3::z()).norm() < 1e-9);
+    }
+
+    #[test]
+    fn look_at_rejects_degenerate_vectors() {
+        [
+            (Vector3::z(), Vector3::z()),
+            (Vector3::z(), -2. * Vector3::z()),
+            (Vector3::zeros(), Vector3::y()),
+            (Vector3::x(), Vector3::zeros()),
+        ]
+        .into_iter()
+        .for_each(|(direction, up)| {
+            assert!(matches!(
+                Rotation::LookAt { direction, up }.to_quaternion(),
+                Err(AUTDInternalError::InvalidLookAt)
+            ));
+        });
+    }
+}
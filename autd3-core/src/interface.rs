@@ -13,32 +13,75 @@
 
 use autd3_driver::TxDatagram;
 
+use crate::error::AUTDInternalError;
 use crate::geometry::{Geometry, Transducer};
-use anyhow::Result;
-
-pub struct Empty;
-pub struct Filled;
 
+/// Data which can be sent to the devices
+///
+/// A header, a body, or a tuple of them is sent by [Sendable::operation].
 pub trait Sendable<T: Transducer> {
-    type H;
-    type B;
-    fn init(&mut self) -> Result<()>;
-    fn pack(&mut self, msg_id: u8, geometry: &Geometry<T>, tx: &mut TxDatagram) -> Result<()>;
-    fn is_finished(&self) -> bool;
+    type H: DatagramHeader;
+    type B: DatagramBody<T>;
+    fn operation(self) -> (Self::H, Self::B);
 }
 
 pub trait DatagramHeader {
-    fn init(&mut self) -> Result<()>;
-    fn pack(&mut self, msg_id: u8, tx: &mut TxDatagram) -> Result<()>;
+    fn init(&mut self) -> Result<(), AUTDInternalError>;
+    fn pack(&mut self, msg_id: u8, tx: &mut TxDatagram) -> Result<(), AUTDInternalError>;
     fn is_finished(&self) -> bool;
 }
 
 pub trait DatagramBody<T: Transducer> {
-    fn init(&mut self) -> Result<()>;
-    fn pack(&mut self, geometry: &Geometry<T>, tx: &mut TxDatagram) -> Result<()>;
+    fn init(&mut self) -> Result<(), AUTDInternalError>;
+    fn pack(
+        &mut self,
+        geometry: &Geometry<T>,
+        tx: &mut TxDatagram,
+    ) -> Result<(), AUTDInternalError>;
     fn is_finished(&self) -> bool;
 }
 
+impl<H: DatagramHeader + ?Sized> DatagramHeader for &mut H {
+    fn init(&mut self) -> Result<(), AUTDInternalError> {
+        H::init(self)
+    }
+
+    fn pack(&mut self, msg_id: u8, tx: &mut TxDatagram) -> Result<(), AUTDInternalError> {
+        H::pack(self, msg_id, tx)
+    }
+
+    fn is_finished(&self) -> bool {
+        H::is_finished(self)
+    }
+}
+
+impl<T: Transducer, B: DatagramBody<T> + ?Sized> DatagramBody<T> for &mut B {
+    fn init(&mut self) -> Result<(), AUTDInternalError> {
+        B::init(self)
+    }
+
+    fn pack(
+        &mut self,
+        geometry: &Geometry<T>,
+        tx: &mut TxDatagram,
+    ) -> Result<(), AUTDInternalError> {
+        B::pack(self, geometry, tx)
+    }
+
+    fn is_finished(&self) -> bool {
+        B::is_finished(self)
+    }
+}
+
+impl<T: Transducer, H: DatagramHeader, B: DatagramBody<T>> Sendable<T> for (H, B) {
+    type H = H;
+    type B = B;
+
+    fn operation(self) -> (Self::H, Self::B) {
+        self
+    }
+}
+
 #[derive(Default)]
 pub struct NullHeader {}
 
@@ -49,11 +92,11 @@ impl NullHeader {
 }
 
 impl DatagramHeader for NullHeader {
-    fn init(&mut self) -> Result<()> {
+    fn init(&mut self) -> Result<(), AUTDInternalError> {
         Ok(())
     }
 
-    fn pack(&mut self, msg_id: u8, tx: &mut TxDatagram) -> Result<()> {
+    fn pack(&mut self, msg_id: u8, tx: &mut TxDatagram) -> Result<(), AUTDInternalError> {
         autd3_driver::null_header(msg_id, tx);
         Ok(())
     }
@@ -73,11 +116,15 @@ impl NullBody {
 }
 
 impl<T: Transducer> DatagramBody<T> for NullBody {
-    fn init(&mut self) -> Result<()> {
+    fn init(&mut self) -> Result<(), AUTDInternalError> {
         Ok(())
     }
 
-    fn pack(&mut self, _geometry: &Geometry<T>, tx: &mut TxDatagram) -> Result<()> {
+    fn pack(
+        &mut self,
+        _geometry: &Geometry<T>,
+        tx: &mut TxDatagram,
+    ) -> Result<(), AUTDInternalError> {
         autd3_driver::null_body(tx);
         Ok(())
     }
@@ -86,3 +133,21 @@ impl<T: Transducer> DatagramBody<T> for NullBody {
         true
     }
 }
+
+impl<T: Transducer> Sendable<T> for NullHeader {
+    type H = Self;
+    type B = NullBody;
+
+    fn operation(self) -> (Self::H, Self::B) {
+        (self, NullBody::new())
+    }
+}
+
+impl<T: Transducer> Sendable<T> for NullBody {
+    type H = NullHeader;
+    type B = Self;
+
+    fn operation(self) -> (Self::H, Self::B) {
+        (NullHeader::new(), self)
+    }
+}
@@ -20,6 +20,7 @@ pub mod link;
 pub mod modulation;
 pub mod silencer_config;
 pub mod stm;
+pub mod timer;
 pub mod utils;
 
 pub use autd3_driver::*;
@@ -11,16 +11,160 @@
  *
  */
 
-use crate::geometry::{Geometry, Transducer};
+use crate::geometry::{Geometry, LegacyTransducer, Transducer};
 
-use anyhow::Result;
+use crate::error::AUTDInternalError;
 use autd3_driver::{RxDatagram, TxDatagram};
 
+/// Event occurred in the link
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LinkEvent {
+    /// The device of the index is lost
+    DeviceLost(usize),
+    /// The lost device of the index is recovered. The data on the device has been cleared.
+    DeviceRecovered(usize),
+    /// Error message reported by the link
+    Error(String),
+}
+
 /// Link is a interface to the AUTD device.
 pub trait Link: Send {
-    fn open<T: Transducer>(&mut self, geometry: &Geometry<T>) -> Result<()>;
-    fn close(&mut self) -> Result<()>;
-    fn send(&mut self, tx: &TxDatagram) -> Result<bool>;
-    fn receive(&mut self, rx: &mut RxDatagram) -> Result<bool>;
+    fn open<T: Transducer>(&mut self, geometry: &Geometry<T>) -> Result<(), AUTDInternalError>;
+    fn close(&mut self) -> Result<(), AUTDInternalError>;
+    fn send(&mut self, tx: &TxDatagram) -> Result<bool, AUTDInternalError>;
+    fn receive(&mut self, rx: &mut RxDatagram) -> Result<bool, AUTDInternalError>;
+    fn is_open(&self) -> bool;
+    /// Return events occurred since the last call
+    fn poll_events(&mut self) -> Vec<LinkEvent> {
+        vec![]
+    }
+}
+
+/// Object-safe counterpart of [Link]
+///
+/// Every [Link] implements this trait, and `Box<dyn DynLink>` implements [Link],
+/// so that a link can be selected at runtime. The transducer type of the geometry is erased at open,
+/// since links only use positions and rotations of the devices.
+///
+/// ```
+/// use autd3_core::{
+///     error::AUTDInternalError,
+///     geometry::{Geometry, GeometryBuilder, Transducer, Vector3},
+///     link::{DynLink, Link},
+///     RxDatagram, TxDatagram,
+/// };
+///
+/// #[derive(Default)]
+/// struct Nop {
+///     is_open: bool,
+/// }
+///
+/// impl Link for Nop {
+///     fn open<T: Transducer>(&mut self, _: &Geometry<T>) -> Result<(), AUTDInternalError> {
+///         self.is_open = true;
+///         Ok(())
+///     }
+///     fn close(&mut self) -> Result<(), AUTDInternalError> {
+///         self.is_open = false;
+///         Ok(())
+///     }
+///     fn send(&mut self, _: &TxDatagram) -> Result<bool, AUTDInternalError> {
+///         Ok(true)
+///     }
+///     fn receive(&mut self, _: &mut RxDatagram) -> Result<bool, AUTDInternalError> {
+///         Ok(true)
+///     }
+///     fn is_open(&self) -> bool {
+///         self.is_open
+///     }
+/// }
+///
+/// let name = "nop";
+/// let mut link: Box<dyn DynLink> = match name {
+///     "nop" => Box::new(Nop::default()),
+///     _ => unimplemented!(),
+/// };
+///
+/// let mut geometry = GeometryBuilder::new().build();
+/// geometry.add_device(Vector3::zeros(), Vector3::zeros());
+/// link.open(&geometry).unwrap();
+/// assert!(Link::is_open(&link));
+/// ```
+pub trait DynLink: Send {
+    fn open_dyn(&mut self, geometry: &Geometry<LegacyTransducer>) -> Result<(), AUTDInternalError>;
+    fn close_dyn(&mut self) -> Result<(), AUTDInternalError>;
+    fn send_dyn(&mut self, tx: &TxDatagram) -> Result<bool, AUTDInternalError>;
+    fn receive_dyn(&mut self, rx: &mut RxDatagram) -> Result<bool, AUTDInternalError>;
+    fn is_open_dyn(&self) -> bool;
+    fn poll_events_dyn(&mut self) -> Vec<LinkEvent>;
+}
+
+impl<L: Link> DynLink for L {
+    fn open_dyn(&mut self, geometry: &Geometry<LegacyTransducer>) -> Result<(), AUTDInternalError> {
+        self.open(geometry)
+    }
+
+    fn close_dyn(&mut self) -> Result<(), AUTDInternalError> {
+        self.close()
+    }
+
+    fn send_dyn(&mut self, tx: &TxDatagram) -> Result<bool, AUTDInternalError> {
+        self.send(tx)
+    }
+
+    fn receive_dyn(&mut self, rx: &mut RxDatagram) -> Result<bool, AUTDInternalError> {
+        self.receive(rx)
+    }
+
+    fn is_open_dyn(&self) -> bool {
+        self.is_open()
+    }
+
+    fn poll_events_dyn(&mut self) -> Vec<LinkEvent> {
+        self.poll_events()
+    }
+}
+
+impl Link for Box<dyn DynLink> {
+    fn open<T: Transducer>(&mut self, geometry: &Geometry<T>) -> Result<(), AUTDInternalError> {
+        self.as_mut().open_dyn(&geometry.with_transducer())
+    }
+
+    fn close(&mut self) -> Result<(), AUTDInternalError> {
+        self.as_mut().close_dyn()
+    }
+
+    fn send(&mut self, tx: &TxDatagram) -> Result<bool, AUTDInternalError> {
+        self.as_mut().send_dyn(tx)
+    }
+
+    fn receive(&mut self, rx: &mut RxDatagram) -> Result<bool, AUTDInternalError> {
+        self.as_mut().receive_dyn(rx)
+    }
+
+    fn is_open(&self) -> bool {
+        self.as_ref().is_open_dyn()
+    }
+
+    fn poll_events(&mut self) -> Vec<LinkEvent> {
+        self.as_mut().poll_events_dyn()
+    }
+}
+
+/// AsyncLink is an asynchronous interface to the AUTD device.
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+pub trait AsyncLink: Send {
+    async fn open<T: Transducer + Sync>(
+        &mut self,
+        geometry: &Geometry<T>,
+    ) -> Result<(), AUTDInternalError>;
+    async fn close(&mut self) -> Result<(), AUTDInternalError>;
+    async fn send(&mut self, tx: &TxDatagram) -> Result<bool, AUTDInternalError>;
+    async fn receive(&mut self, rx: &mut RxDatagram) -> Result<bool, AUTDInternalError>;
     fn is_open(&self) -> bool;
+    /// Return events occurred since the last call
+    fn poll_events(&mut self) -> Vec<LinkEvent> {
+        vec![]
+    }
 }
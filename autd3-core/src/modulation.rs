@@ -11,7 +11,7 @@
  *
  */
 
-use anyhow::Result;
+use crate::error::AUTDInternalError;
 
 use crate::interface::DatagramHeader;
 
@@ -41,8 +41,8 @@ impl Default for ModProps {
 
 /// Modulation contains the amplitude modulation data.
 pub trait Modulation: DatagramHeader {
-    fn build(&mut self) -> Result<()>;
-    fn rebuild(&mut self) -> Result<()>;
+    fn build(&mut self) -> Result<(), AUTDInternalError>;
+    fn rebuild(&mut self) -> Result<(), AUTDInternalError>;
     fn buffer(&self) -> &[u8];
     fn sampling_frequency_division(&mut self) -> &mut u32;
     fn sampling_freq(&self) -> f64;
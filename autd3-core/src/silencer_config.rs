@@ -11,11 +11,11 @@
  *
  */
 
+use crate::error::AUTDInternalError;
 use crate::{
-    geometry::{Geometry, Transducer},
-    interface::{DatagramHeader, Empty, Filled, Sendable},
+    geometry::Transducer,
+    interface::{DatagramHeader, NullBody, Sendable},
 };
-use anyhow::Result;
 use autd3_driver::TxDatagram;
 
 pub struct SilencerConfig {
@@ -39,40 +39,42 @@ impl SilencerConfig {
 }
 
 impl DatagramHeader for SilencerConfig {
-    fn init(&mut self) -> Result<()> {
+    fn init(&mut self) -> Result<(), AUTDInternalError> {
         self.sent = false;
         Ok(())
     }
 
-    fn pack(&mut self, msg_id: u8, tx: &mut TxDatagram) -> Result<()> {
+    fn pack(&mut self, msg_id: u8, tx: &mut TxDatagram) -> Result<(), AUTDInternalError> {
         if self.sent {
             autd3_driver::null_header(msg_id, tx);
             Ok(())
         } else {
             self.sent = true;
-            autd3_driver::config_silencer(msg_id, self.cycle, self.step, tx)
+            autd3_driver::config_silencer(msg_id, self.cycle, self.step, tx)?;
+            Ok(())
         }
     }
 
     fn is_finished(&self) -> bool {
-        true
+        self.sent
     }
 }
 
 impl<T: Transducer> Sendable<T> for SilencerConfig {
-    type H = Filled;
-    type B = Empty;
+    type H = Self;
+    type B = NullBody;
 
-    fn init(&mut self) -> Result<()> {
-        DatagramHeader::init(self)
+    fn operation(self) -> (Self::H, Self::B) {
+        (self, NullBody::new())
     }
+}
 
-    fn pack(&mut self, msg_id: u8, _geometry: &Geometry<T>, tx: &mut TxDatagram) -> Result<()> {
-        DatagramHeader::pack(self, msg_id, tx)
-    }
+impl<T: Transducer> Sendable<T> for &mut SilencerConfig {
+    type H = Self;
+    type B = NullBody;
 
-    fn is_finished(&self) -> bool {
-        DatagramHeader::is_finished(self)
+    fn operation(self) -> (Self::H, Self::B) {
+        (self, NullBody::new())
     }
 }
 
@@ -16,10 +16,10 @@ use std::marker::PhantomData;
 use crate::{
     gain::Gain,
     geometry::{Geometry, LegacyTransducer, NormalPhaseTransducer, NormalTransducer, Transducer},
-    interface::{DatagramBody, Empty, Filled, Sendable},
+    interface::{DatagramBody, NullHeader, Sendable},
 };
 
-use anyhow::{Ok, Result};
+use crate::error::AUTDInternalError;
 use autd3_driver::{Drive, Mode, TxDatagram, FPGA_CLK_FREQ, STM_SAMPLING_FREQ_DIV_MIN};
 
 use super::STM;
@@ -53,7 +53,11 @@ impl<T: Transducer> GainSTM<T> {
         self.mode = mode;
     }
 
-    pub fn add<G: Gain<T>>(&mut self, gain: G, geometry: &Geometry<T>) -> Result<()> {
+    pub fn add<G: Gain<T>>(
+        &mut self,
+        gain: G,
+        geometry: &Geometry<T>,
+    ) -> Result<(), AUTDInternalError> {
         if self.gains.len() + 1 > autd3_driver::GAIN_STM_BUF_SIZE_MAX {
             return Err(autd3_driver::FPGAError::GainSTMOutOfBuffer(self.gains.len() + 1).into());
         }
@@ -71,6 +75,13 @@ impl<T: Transducer> GainSTM<T> {
     pub fn size(&self) -> usize {
         self.gains.len()
     }
+
+    /// Return the `idx`-th drives in the layout of the frames, or empty if it does not exist
+    fn packed(&self, geometry: &Geometry<T>, idx: usize) -> Vec<Drive> {
+        self.gains
+            .get(idx)
+            .map_or_else(Vec::new, |d| geometry.pack_drives(d).into_owned())
+    }
 }
 
 impl<T: Transducer> Default for GainSTM<T> {
@@ -80,12 +91,16 @@ impl<T: Transducer> Default for GainSTM<T> {
 }
 
 impl DatagramBody<LegacyTransducer> for GainSTM<LegacyTransducer> {
-    fn init(&mut self) -> Result<()> {
+    fn init(&mut self) -> Result<(), AUTDInternalError> {
         self.sent = 0;
         Ok(())
     }
 
-    fn pack(&mut self, _geometry: &Geometry<LegacyTransducer>, tx: &mut TxDatagram) -> Result<()> {
+    fn pack(
+        &mut self,
+        geometry: &Geometry<LegacyTransducer>,
+        tx: &mut TxDatagram,
+    ) -> Result<(), AUTDInternalError> {
         autd3_driver::gain_stm_legacy_head(tx);
 
         if DatagramBody::<LegacyTransducer>::is_finished(self) {
@@ -111,7 +126,7 @@ impl DatagramBody<LegacyTransducer> for GainSTM<LegacyTransducer> {
             Mode::PhaseDutyFull => {
                 let is_last_frame = self.sent + 1 == self.gains.len() + 1;
                 autd3_driver::gain_stm_legacy_body(
-                    &[&self.gains[self.sent - 1]],
+                    &[&geometry.pack_drives(&self.gains[self.sent - 1])],
                     is_first_frame,
                     self.sample_freq_div,
                     is_last_frame,
@@ -121,11 +136,11 @@ impl DatagramBody<LegacyTransducer> for GainSTM<LegacyTransducer> {
                 self.sent += 1;
             }
             Mode::PhaseFull => {
-                let is_last_frame = self.sent + 2 >= self.gains.len() + 1;
+                let is_last_frame = self.sent + 2 > self.gains.len();
                 autd3_driver::gain_stm_legacy_body(
                     &[
-                        &self.gains[self.sent - 1],
-                        self.gains.get(self.sent + 1 - 1).unwrap_or(&Vec::new()),
+                        &geometry.pack_drives(&self.gains[self.sent - 1]),
+                        &self.packed(geometry, self.sent + 1 - 1),
                     ],
                     is_first_frame,
                     self.sample_freq_div,
@@ -136,13 +151,13 @@ impl DatagramBody<LegacyTransducer> for GainSTM<LegacyTransducer> {
                 self.sent += 2;
             }
             Mode::PhaseHalf => {
-                let is_last_frame = self.sent + 4 >= self.gains.len() + 1;
+                let is_last_frame = self.sent + 4 > self.gains.len();
                 autd3_driver::gain_stm_legacy_body(
                     &[
-                        &self.gains[self.sent - 1],
-                        self.gains.get(self.sent + 1 - 1).unwrap_or(&Vec::new()),
-                        self.gains.get(self.sent + 2 - 1).unwrap_or(&Vec::new()),
-                        self.gains.get(self.sent + 3 - 1).unwrap_or(&Vec::new()),
+                        &geometry.pack_drives(&self.gains[self.sent - 1]),
+                        &self.packed(geometry, self.sent + 1 - 1),
+                        &self.packed(geometry, self.sent + 2 - 1),
+                        &self.packed(geometry, self.sent + 3 - 1),
                     ],
                     is_first_frame,
                     self.sample_freq_div,
@@ -163,13 +178,17 @@ impl DatagramBody<LegacyTransducer> for GainSTM<LegacyTransducer> {
 }
 
 impl DatagramBody<NormalTransducer> for GainSTM<NormalTransducer> {
-    fn init(&mut self) -> Result<()> {
+    fn init(&mut self) -> Result<(), AUTDInternalError> {
         self.sent = 0;
         self.next_duty = false;
         Ok(())
     }
 
-    fn pack(&mut self, _geometry: &Geometry<NormalTransducer>, tx: &mut TxDatagram) -> Result<()> {
+    fn pack(
+        &mut self,
+        geometry: &Geometry<NormalTransducer>,
+        tx: &mut TxDatagram,
+    ) -> Result<(), AUTDInternalError> {
         autd3_driver::gain_stm_normal_head(tx);
 
         if DatagramBody::<NormalTransducer>::is_finished(self) {
@@ -203,7 +222,7 @@ impl DatagramBody<NormalTransducer> for GainSTM<NormalTransducer> {
                 self.sent - 1
             };
             autd3_driver::gain_stm_normal_phase_body(
-                &self.gains[idx],
+                &geometry.pack_drives(&self.gains[idx]),
                 is_first_frame,
                 self.sample_freq_div,
                 self.mode,
@@ -212,7 +231,7 @@ impl DatagramBody<NormalTransducer> for GainSTM<NormalTransducer> {
             )?;
         } else {
             autd3_driver::gain_stm_normal_duty_body(
-                &self.gains[(self.sent - 1) / 2],
+                &geometry.pack_drives(&self.gains[(self.sent - 1) / 2]),
                 is_last_frame,
                 tx,
             )?;
@@ -236,7 +255,7 @@ impl DatagramBody<NormalTransducer> for GainSTM<NormalTransducer> {
 }
 
 impl DatagramBody<NormalPhaseTransducer> for GainSTM<NormalPhaseTransducer> {
-    fn init(&mut self) -> Result<()> {
+    fn init(&mut self) -> Result<(), AUTDInternalError> {
         self.sent = 0;
         self.next_duty = false;
         Ok(())
@@ -244,9 +263,9 @@ impl DatagramBody<NormalPhaseTransducer> for GainSTM<NormalPhaseTransducer> {
 
     fn pack(
         &mut self,
-        _geometry: &Geometry<NormalPhaseTransducer>,
+        geometry: &Geometry<NormalPhaseTransducer>,
         tx: &mut TxDatagram,
-    ) -> Result<()> {
+    ) -> Result<(), AUTDInternalError> {
         autd3_driver::gain_stm_normal_head(tx);
 
         if DatagramBody::<NormalPhaseTransducer>::is_finished(self) {
@@ -271,7 +290,7 @@ impl DatagramBody<NormalPhaseTransducer> for GainSTM<NormalPhaseTransducer> {
 
         let idx = self.sent - 1;
         autd3_driver::gain_stm_normal_phase_body(
-            &self.gains[idx],
+            &geometry.pack_drives(&self.gains[idx]),
             is_first_frame,
             self.sample_freq_div,
             self.mode,
@@ -316,67 +335,55 @@ impl<T: Transducer> STM for GainSTM<T> {
 }
 
 impl Sendable<LegacyTransducer> for GainSTM<LegacyTransducer> {
-    type H = Empty;
-    type B = Filled;
+    type H = NullHeader;
+    type B = Self;
 
-    fn init(&mut self) -> Result<()> {
-        DatagramBody::<LegacyTransducer>::init(self)
+    fn operation(self) -> (Self::H, Self::B) {
+        (NullHeader::new(), self)
     }
+}
 
-    fn pack(
-        &mut self,
-        _msg_id: u8,
-        geometry: &Geometry<LegacyTransducer>,
-        tx: &mut TxDatagram,
-    ) -> Result<()> {
-        DatagramBody::<LegacyTransducer>::pack(self, geometry, tx)
-    }
+impl Sendable<LegacyTransducer> for &mut GainSTM<LegacyTransducer> {
+    type H = NullHeader;
+    type B = Self;
 
-    fn is_finished(&self) -> bool {
-        DatagramBody::<LegacyTransducer>::is_finished(self)
+    fn operation(self) -> (Self::H, Self::B) {
+        (NullHeader::new(), self)
     }
 }
 
 impl Sendable<NormalTransducer> for GainSTM<NormalTransducer> {
-    type H = Empty;
-    type B = Filled;
+    type H = NullHeader;
+    type B = Self;
 
-    fn init(&mut self) -> Result<()> {
-        DatagramBody::<NormalTransducer>::init(self)
+    fn operation(self) -> (Self::H, Self::B) {
+        (NullHeader::new(), self)
     }
+}
 
-    fn pack(
-        &mut self,
-        _msg_id: u8,
-        geometry: &Geometry<NormalTransducer>,
-        tx: &mut TxDatagram,
-    ) -> Result<()> {
-        DatagramBody::<NormalTransducer>::pack(self, geometry, tx)
-    }
+impl Sendable<NormalTransducer> for &mut GainSTM<NormalTransducer> {
+    type H = NullHeader;
+    type B = Self;
 
-    fn is_finished(&self) -> bool {
-        DatagramBody::<NormalTransducer>::is_finished(self)
+    fn operation(self) -> (Self::H, Self::B) {
+        (NullHeader::new(), self)
     }
 }
 
 impl Sendable<NormalPhaseTransducer> for GainSTM<NormalPhaseTransducer> {
-    type H = Empty;
-    type B = Filled;
+    type H = NullHeader;
+    type B = Self;
 
-    fn init(&mut self) -> Result<()> {
-        DatagramBody::<NormalPhaseTransducer>::init(self)
+    fn operation(self) -> (Self::H, Self::B) {
+        (NullHeader::new(), self)
     }
+}
 
-    fn pack(
-        &mut self,
-        _msg_id: u8,
-        geometry: &Geometry<NormalPhaseTransducer>,
-        tx: &mut TxDatagram,
-    ) -> Result<()> {
-        DatagramBody::<NormalPhaseTransducer>::pack(self, geometry, tx)
-    }
+impl Sendable<NormalPhaseTransducer> for &mut GainSTM<NormalPhaseTransducer> {
+    type H = NullHeader;
+    type B = Self;
 
-    fn is_finished(&self) -> bool {
-        DatagramBody::<NormalPhaseTransducer>::is_finished(self)
+    fn operation(self) -> (Self::H, Self::B) {
+        (NullHeader::new(), self)
     }
 }
@@ -13,10 +13,10 @@
 
 use crate::{
     geometry::{Geometry, Transducer, Vector3},
-    interface::{DatagramBody, Empty, Filled, Sendable},
+    interface::{DatagramBody, NullHeader, Sendable},
 };
 
-use anyhow::{Ok, Result};
+use crate::error::AUTDInternalError;
 use autd3_driver::{
     SeqFocus, TxDatagram, FPGA_CLK_FREQ, POINT_STM_BODY_DATA_SIZE, POINT_STM_HEAD_DATA_SIZE,
     STM_SAMPLING_FREQ_DIV_MIN,
@@ -24,6 +24,10 @@ use autd3_driver::{
 
 use super::STM;
 
+/// STM of focal points, whose drives are calculated on the devices
+///
+/// Since the devices assume AUTD3 layout and know nothing about calibrations and disabled transducers,
+/// sending this to a device with a custom [DeviceLayout](crate::geometry::DeviceLayout), [Calibration](crate::geometry::Calibration) or disabled transducers results in an error.
 pub struct PointSTM {
     control_points: Vec<(Vector3, u8)>,
     sample_freq_div: u32,
@@ -43,7 +47,7 @@ impl PointSTM {
         }
     }
 
-    pub fn add(&mut self, point: Vector3, duty_shift: u8) -> Result<()> {
+    pub fn add(&mut self, point: Vector3, duty_shift: u8) -> Result<(), AUTDInternalError> {
         if self.control_points.len() + 1 > autd3_driver::POINT_STM_BUF_SIZE_MAX {
             return Err(autd3_driver::FPGAError::PointSTMOutOfBuffer(
                 self.control_points.len() + 1,
@@ -70,18 +74,40 @@ impl Default for PointSTM {
 }
 
 impl<T: Transducer> DatagramBody<T> for PointSTM {
-    fn init(&mut self) -> Result<()> {
+    fn init(&mut self) -> Result<(), AUTDInternalError> {
         self.sent = 0;
         Ok(())
     }
 
-    fn pack(&mut self, geometry: &Geometry<T>, tx: &mut TxDatagram) -> Result<()> {
+    fn pack(
+        &mut self,
+        geometry: &Geometry<T>,
+        tx: &mut TxDatagram,
+    ) -> Result<(), AUTDInternalError> {
         autd3_driver::point_stm_head(tx);
 
         if DatagramBody::<T>::is_finished(self) {
             return Ok(());
         }
 
+        if let Some(i) = geometry
+            .devices()
+            .iter()
+            .position(|dev| !dev.is_autd3_layout())
+        {
+            return Err(AUTDInternalError::PointSTMLayoutNotSupported(i));
+        }
+        if let Some(i) = geometry
+            .devices()
+            .iter()
+            .position(|dev| dev.is_calibrated())
+        {
+            return Err(AUTDInternalError::PointSTMCalibrationNotSupported(i));
+        }
+        if let Some(i) = geometry.devices().iter().position(|dev| dev.has_disabled()) {
+            return Err(AUTDInternalError::PointSTMDisabledNotSupported(i));
+        }
+
         let is_first_frame = self.sent == 0;
         let max_size = if is_first_frame {
             POINT_STM_HEAD_DATA_SIZE
@@ -125,19 +151,20 @@ impl<T: Transducer> DatagramBody<T> for PointSTM {
 }
 
 impl<T: Transducer> Sendable<T> for PointSTM {
-    type H = Empty;
-    type B = Filled;
+    type H = NullHeader;
+    type B = Self;
 
-    fn init(&mut self) -> Result<()> {
-        DatagramBody::<T>::init(self)
+    fn operation(self) -> (Self::H, Self::B) {
+        (NullHeader::new(), self)
     }
+}
 
-    fn pack(&mut self, _msg_id: u8, geometry: &Geometry<T>, tx: &mut TxDatagram) -> Result<()> {
-        DatagramBody::<T>::pack(self, geometry, tx)
-    }
+impl<T: Transducer> Sendable<T> for &mut PointSTM {
+    type H = NullHeader;
+    type B = Self;
 
-    fn is_finished(&self) -> bool {
-        DatagramBody::<T>::is_finished(self)
+    fn operation(self) -> (Self::H, Self::B) {
+        (NullHeader::new(), self)
     }
 }
 
@@ -166,3 +193,82 @@ impl STM for PointSTM {
         self.sample_freq_div
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::{Calibration, GeometryBuilder, GridLayout, LegacyTransducer};
+
+    fn pack(geometry: &Geometry<LegacyTransducer>) -> Result<(), AUTDInternalError> {
+        let mut stm = PointSTM::with_control_points(vec![(Vector3::zeros(), 0); 2]);
+        let mut tx = TxDatagram::new(geometry.num_devices());
+        DatagramBody::<LegacyTransducer>::init(&mut stm)?;
+        stm.pack(geometry, &mut tx)
+    }
+
+    #[test]
+    fn accepts_grid_equivalent_to_autd3() {
+        let mut geometry = GeometryBuilder::new().legacy_mode().build();
+        let grid = GridLayout::new(18, 14, 10.16).with_missing([(1, 1), (2, 1), (16, 1)]);
+        geometry
+            .add_device_with_layout(Vector3::zeros(), Vector3::zeros(), &grid)
+            .unwrap();
+        assert!(geometry.devices()[0].is_autd3_layout());
+        assert!(pack(&geometry).is_ok());
+    }
+
+    #[test]
+    fn rejects_custom_layout() {
+        let mut geometry = GeometryBuilder::new().legacy_mode().build();
+        geometry.add_device(Vector3::zeros(), Vector3::zeros());
+        geometry
+            .add_device_with_layout(
+                Vector3::zeros(),
+                Vector3::zeros(),
+                &GridLayout::new(10, 10, 10.),
+            )
+            .unwrap();
+        assert!(matches!(
+            pack(&geometry),
+            Err(AUTDInternalError::PointSTMLayoutNotSupported(1))
+        ));
+    }
+
+    #[test]
+    fn rejects_calibrated_device() {
+        let mut geometry = GeometryBuilder::new().legacy_mode().build();
+        geometry.add_device(Vector3::zeros(), Vector3::zeros());
+        geometry.add_device(Vector3::zeros(), Vector3::zeros());
+        assert!(pack(&geometry).is_ok());
+
+        *geometry.calibration_mut(300).unwrap() = Calibration::new(0.1, 1.);
+        assert!(matches!(
+            pack(&geometry),
+            Err(AUTDInternalError::PointSTMCalibrationNotSupported(1))
+        ));
+
+        *geometry.calibration_mut(300).unwrap() = Calibration::default();
+        assert!(pack(&geometry).is_ok());
+    }
+
+    #[test]
+    fn rejects_disabled_transducers() {
+        let mut geometry = GeometryBuilder::new().legacy_mode().build();
+        geometry.add_device(Vector3::zeros(), Vector3::zeros());
+        geometry.add_device(Vector3::zeros(), Vector3::zeros());
+
+        geometry.disable_transducer(300).unwrap();
+        assert!(matches!(
+            pack(&geometry),
+            Err(AUTDInternalError::PointSTMDisabledNotSupported(1))
+        ));
+        geometry.enable_transducer(300).unwrap();
+        assert!(pack(&geometry).is_ok());
+
+        geometry.devices_mut()[0].set_enabled(false);
+        assert!(matches!(
+            pack(&geometry),
+            Err(AUTDInternalError::PointSTMDisabledNotSupported(0))
+        ));
+    }
+}
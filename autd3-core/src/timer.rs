@@ -0,0 +1,51 @@
+/*
+ * File: timer.rs
+ * Project: src
+ * Created Date: 18/10/2026
+ * Author: Shun Suzuki
+ * -----
+ * Last Modified: 18/10/2026
+ * Modified By: Shun Suzuki (suzuki@hapis.k.u-tokyo.ac.jp)
+ * -----
+ * Copyright (c) 2026 Shun Suzuki. All rights reserved.
+ *
+ */
+
+use std::time::{Duration, Instant};
+
+/// Margin before the deadline where [HighPrecisionWaiter] stops sleeping and starts spinning
+const SPIN_MARGIN: Duration = Duration::from_millis(1);
+
+/// Strategy to wait for a deadline
+pub trait Waiter {
+    fn wait_until(deadline: Instant);
+}
+
+/// Wait with [std::thread::sleep]
+pub struct NormalWaiter {}
+
+/// Sleep until shortly before the deadline, and spin for the rest
+///
+/// The jitter is much smaller than [NormalWaiter], but one CPU core is busy while spinning.
+pub struct HighPrecisionWaiter {}
+
+impl Waiter for NormalWaiter {
+    fn wait_until(deadline: Instant) {
+        let now = Instant::now();
+        if deadline > now {
+            std::thread::sleep(deadline - now);
+        }
+    }
+}
+
+impl Waiter for HighPrecisionWaiter {
+    fn wait_until(deadline: Instant) {
+        let now = Instant::now();
+        if deadline > now + SPIN_MARGIN {
+            std::thread::sleep(deadline - now - SPIN_MARGIN);
+        }
+        while Instant::now() < deadline {
+            std::hint::spin_loop();
+        }
+    }
+}
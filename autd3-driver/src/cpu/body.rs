@@ -96,7 +96,7 @@ impl SeqFocus {
             ((y << 2) & 0xFFFC) as u16 | ((x >> 30) & 0x0002) as u16 | ((x >> 16) & 0x0001) as u16;
         let d2 =
             ((z << 4) & 0xFFF0) as u16 | ((y >> 28) & 0x0008) as u16 | ((y >> 14) & 0x0007) as u16;
-        let d3 = (((duty_shift as u16) << 6) & 0x3FC0) as u16
+        let d3 = ((duty_shift as u16) << 6) & 0x3FC0
             | ((z >> 26) & 0x0020) as u16
             | ((z >> 12) & 0x001F) as u16;
         SeqFocus {
@@ -23,5 +23,6 @@ pub use body::*;
 pub use cpu_defined::*;
 pub use datagram::*;
 pub use ec_config::*;
+pub use error::CPUError;
 pub use header::*;
 pub use operation::*;
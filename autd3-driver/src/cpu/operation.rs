@@ -16,14 +16,13 @@ use crate::{
         error::CPUError, CPUControlFlags, TxDatagram, MOD_BODY_DATA_SIZE, MOD_HEAD_DATA_SIZE,
         MSG_CLEAR, MSG_RD_CPU_VERSION, MSG_RD_FPGA_FUNCTION, MSG_RD_FPGA_VERSION,
     },
+    error::DriverError,
     fpga::{FPGAControlFlags, FPGAError, MOD_SAMPLING_FREQ_DIV_MIN, SILENCER_CYCLE_MIN},
     hardware::NUM_TRANS_IN_UNIT,
     Drive, Mode, SeqFocus, POINT_STM_BODY_DATA_SIZE, POINT_STM_HEAD_DATA_SIZE,
     STM_SAMPLING_FREQ_DIV_MIN,
 };
 
-use anyhow::Result;
-
 pub fn clear(tx: &mut TxDatagram) {
     tx.header_mut().msg_id = MSG_CLEAR;
     tx.num_bodies = 0;
@@ -48,7 +47,11 @@ pub fn null_body(tx: &mut TxDatagram) {
     tx.num_bodies = 0;
 }
 
-pub fn sync(msg_id: u8, cycles: &[[u16; NUM_TRANS_IN_UNIT]], tx: &mut TxDatagram) -> Result<()> {
+pub fn sync(
+    msg_id: u8,
+    cycles: &[[u16; NUM_TRANS_IN_UNIT]],
+    tx: &mut TxDatagram,
+) -> Result<(), DriverError> {
     if cycles.len() != tx.body().len() {
         return Err(CPUError::DeviceNumberNotCorrect {
             a: tx.body().len(),
@@ -83,7 +86,7 @@ pub fn modulation(
     freq_div: u32,
     is_last_frame: bool,
     tx: &mut TxDatagram,
-) -> Result<()> {
+) -> Result<(), DriverError> {
     tx.header_mut().msg_id = msg_id;
     tx.header_mut().cpu_flag.set(CPUControlFlags::MOD, true);
     tx.header_mut().cpu_flag.remove(CPUControlFlags::MOD_BEGIN);
@@ -123,7 +126,12 @@ pub fn modulation(
     Ok(())
 }
 
-pub fn config_silencer(msg_id: u8, cycle: u16, step: u16, tx: &mut TxDatagram) -> Result<()> {
+pub fn config_silencer(
+    msg_id: u8,
+    cycle: u16,
+    step: u16,
+    tx: &mut TxDatagram,
+) -> Result<(), DriverError> {
     if cycle < SILENCER_CYCLE_MIN {
         return Err(FPGAError::SilencerCycleOutOfRange(cycle).into());
     }
@@ -143,7 +151,10 @@ pub fn config_silencer(msg_id: u8, cycle: u16, step: u16, tx: &mut TxDatagram) -
     Ok(())
 }
 
-pub fn mod_delay(delays: &[[u16; NUM_TRANS_IN_UNIT]], tx: &mut TxDatagram) -> Result<()> {
+pub fn mod_delay(
+    delays: &[[u16; NUM_TRANS_IN_UNIT]],
+    tx: &mut TxDatagram,
+) -> Result<(), DriverError> {
     if delays.len() != tx.body().len() {
         return Err(CPUError::DeviceNumberNotCorrect {
             a: tx.body().len(),
@@ -181,7 +192,7 @@ pub fn normal_legacy_head(tx: &mut TxDatagram) {
     tx.num_bodies = 0;
 }
 
-pub fn normal_legacy_body(drive: &[Drive], tx: &mut TxDatagram) -> Result<()> {
+pub fn normal_legacy_body(drive: &[Drive], tx: &mut TxDatagram) -> Result<(), DriverError> {
     if drive.len() / NUM_TRANS_IN_UNIT != tx.body().len() {
         return Err(CPUError::DeviceNumberNotCorrect {
             a: tx.body().len(),
@@ -221,7 +232,7 @@ pub fn normal_head(tx: &mut TxDatagram) {
     tx.num_bodies = 0;
 }
 
-pub fn normal_duty_body(drive: &[Drive], tx: &mut TxDatagram) -> Result<()> {
+pub fn normal_duty_body(drive: &[Drive], tx: &mut TxDatagram) -> Result<(), DriverError> {
     if drive.len() / NUM_TRANS_IN_UNIT != tx.body().len() {
         return Err(CPUError::DeviceNumberNotCorrect {
             a: tx.body().len(),
@@ -250,7 +261,7 @@ pub fn normal_duty_body(drive: &[Drive], tx: &mut TxDatagram) -> Result<()> {
     Ok(())
 }
 
-pub fn normal_phase_body(drive: &[Drive], tx: &mut TxDatagram) -> Result<()> {
+pub fn normal_phase_body(drive: &[Drive], tx: &mut TxDatagram) -> Result<(), DriverError> {
     if drive.len() / NUM_TRANS_IN_UNIT != tx.body().len() {
         return Err(CPUError::DeviceNumberNotCorrect {
             a: tx.body().len(),
@@ -302,7 +313,7 @@ pub fn point_stm_body(
     sound_speed: f64,
     is_last_frame: bool,
     tx: &mut TxDatagram,
-) -> Result<()> {
+) -> Result<(), DriverError> {
     if points.is_empty() || points[0].is_empty() {
         return Ok(());
     }
@@ -381,7 +392,7 @@ pub fn gain_stm_legacy_body(
     is_last_frame: bool,
     mode: Mode,
     tx: &mut TxDatagram,
-) -> Result<()> {
+) -> Result<(), DriverError> {
     if is_first_frame {
         if freq_div < STM_SAMPLING_FREQ_DIV_MIN {
             return Err(FPGAError::STMFreqDivOutOfRange(freq_div).into());
@@ -513,7 +524,7 @@ pub fn gain_stm_normal_phase_body(
     mode: Mode,
     is_last_frame: bool,
     tx: &mut TxDatagram,
-) -> Result<()> {
+) -> Result<(), DriverError> {
     if mode == Mode::PhaseHalf {
         return Err(CPUError::PhaseHalfNotSupported.into());
     }
@@ -561,7 +572,7 @@ pub fn gain_stm_normal_duty_body(
     drives: &[Drive],
     is_last_frame: bool,
     tx: &mut TxDatagram,
-) -> Result<()> {
+) -> Result<(), DriverError> {
     tx.header_mut().cpu_flag.set(CPUControlFlags::IS_DUTY, true);
 
     tx.body_mut()
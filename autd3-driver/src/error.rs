@@ -0,0 +1,24 @@
+/*
+ * File: error.rs
+ * Project: src
+ * Created Date: 18/10/2026
+ * Author: Shun Suzuki
+ * -----
+ * Last Modified: 18/10/2026
+ * Modified By: Shun Suzuki (suzuki@hapis.k.u-tokyo.ac.jp)
+ * -----
+ * Copyright (c) 2026 Shun Suzuki. All rights reserved.
+ *
+ */
+
+use thiserror::Error;
+
+use crate::{CPUError, FPGAError};
+
+#[derive(Error, Debug)]
+pub enum DriverError {
+    #[error(transparent)]
+    CPU(#[from] CPUError),
+    #[error(transparent)]
+    FPGA(#[from] FPGAError),
+}
@@ -18,6 +18,7 @@ const ENABLED_MODULATOR_BIT: u8 = 0x02;
 const ENABLED_SILENCER_BIT: u8 = 0x04;
 const ENABLED_MOD_DELAY_BIT: u8 = 0x08;
 
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct FirmwareInfo {
     idx: usize,
     cpu_version_number: u8,
@@ -40,6 +41,15 @@ impl FirmwareInfo {
         }
     }
 
+    pub fn idx(&self) -> usize {
+        self.idx
+    }
+
+    /// Return true if CPU and FPGA firmware versions are the same
+    pub fn is_version_matched(&self) -> bool {
+        self.cpu_version_number == self.fpga_version_number
+    }
+
     pub fn cpu_version(&self) -> String {
         Self::firmware_version_map(self.cpu_version_number)
     }
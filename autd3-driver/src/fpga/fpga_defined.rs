@@ -13,6 +13,8 @@
 
 use std::f64::consts::PI;
 
+use crate::RxMessage;
+
 pub const FPGA_CLK_FREQ: usize = 163840000;
 
 pub const MAX_CYCLE: u16 = 8191;
@@ -88,14 +90,24 @@ impl Duty {
     }
 }
 
-#[derive(Default)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 #[repr(C)]
 pub struct FPGAInfo {
     info: u8,
 }
 
 impl FPGAInfo {
+    pub fn new(info: u8) -> Self {
+        Self { info }
+    }
+
     pub fn is_thermal_assert(&self) -> bool {
         (self.info & 0x01) != 0
     }
 }
+
+impl From<&RxMessage> for FPGAInfo {
+    fn from(msg: &RxMessage) -> Self {
+        Self::new(msg.ack)
+    }
+}
@@ -1,9 +1,11 @@
 pub mod cpu;
+pub mod error;
 pub mod firmware_version;
 pub mod fpga;
 pub mod hardware;
 
 pub use cpu::*;
+pub use error::DriverError;
 pub use firmware_version::*;
 pub use fpga::*;
 pub use hardware::*;
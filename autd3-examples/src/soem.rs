@@ -14,6 +14,8 @@
 mod test_runner;
 mod tests;
 
+use std::time::Duration;
+
 use anyhow::Result;
 
 use autd3::prelude::*;
@@ -39,7 +41,7 @@ fn main() -> Result<()> {
 
     let mut autd = Controller::open(geometry, link).expect("Failed to open");
 
-    autd.check_trials = 50;
+    autd.send_policy.timeout = Duration::from_millis(25);
 
     run!(autd);
 
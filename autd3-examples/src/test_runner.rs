@@ -63,7 +63,7 @@ macro_rules! run {
 
             // let res = autd.stop()?;
             let mut m = Static::new(0);
-            let res = autd.send(&mut m).flush()?;
+            let res = autd.send(&mut m)?;
             println!("stop: {}", res);
         }
 
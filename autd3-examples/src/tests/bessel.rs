@@ -15,7 +15,7 @@
 macro_rules! bessel {
     ($autd:ident) => {{
         let mut silencer_config = SilencerConfig::default();
-        $autd.send(&mut silencer_config).flush()?;
+        $autd.send(&mut silencer_config)?;
 
         let center = $autd.geometry().center();
         let dir = Vector3::z();
@@ -23,6 +23,6 @@ macro_rules! bessel {
         let mut g = Bessel::new(center, dir, 18. / 180. * std::f64::consts::PI);
         let mut m = Sine::new(150);
 
-        $autd.send(&mut m).send(&mut g)?;
+        $autd.send((&mut m, &mut g))?;
     }};
 }
@@ -15,13 +15,13 @@
 macro_rules! focus {
     ($autd:ident) => {{
         let mut silencer_config = SilencerConfig::default();
-        $autd.send(&mut silencer_config).flush()?;
+        $autd.send(&mut silencer_config)?;
 
         let center = $autd.geometry().center() + Vector3::new(0., 0., 150.0);
 
         let mut g = Focus::new(center);
         let mut m = Sine::new(150);
 
-        $autd.send(&mut m).send(&mut g)?;
+        $autd.send((&mut m, &mut g))?;
     }};
 }
@@ -15,7 +15,7 @@
 macro_rules! grouped {
     ($autd:ident) => {{
         let mut silencer_config = SilencerConfig::default();
-        $autd.send(&mut silencer_config).flush()?;
+        $autd.send(&mut silencer_config)?;
 
         let g1 = Focus::new($autd.geometry().devices()[0].center() + Vector3::new(0., 0., 150.0));
         let g2 = Bessel::new(
@@ -30,6 +30,6 @@ macro_rules! grouped {
 
         let mut m = Sine::new(150);
 
-        $autd.send(&mut m).send(&mut g)?;
+        $autd.send((&mut m, &mut g))?;
     }};
 }
@@ -17,7 +17,7 @@ macro_rules! holo {
         use autd3_gain_holo::*;
 
         let mut silencer_config = SilencerConfig::default();
-        $autd.send(&mut silencer_config).flush()?;
+        $autd.send(&mut silencer_config)?;
 
         let center = $autd.geometry().center() + Vector3::new(0., 0., 150.0);
 
@@ -43,35 +43,35 @@ macro_rules! holo {
         match s.trim().parse::<usize>() {
             Ok(0) => {
                 let mut g = SDP::<NalgebraBackend, _, _>::new(foci, amps, c);
-                $autd.send(&mut m).send(&mut g)?;
+                $autd.send((&mut m, &mut g))?;
             }
             Ok(1) => {
                 let mut g = EVD::<NalgebraBackend, _, _>::new(foci, amps, c);
-                $autd.send(&mut m).send(&mut g)?;
+                $autd.send((&mut m, &mut g))?;
             }
             Ok(2) => {
                 let mut g = Naive::<NalgebraBackend, _, _>::new(foci, amps, c);
-                $autd.send(&mut m).send(&mut g)?;
+                $autd.send((&mut m, &mut g))?;
             }
             Ok(3) => {
                 let mut g = GS::<NalgebraBackend, _, _>::new(foci, amps, c);
-                $autd.send(&mut m).send(&mut g)?;
+                $autd.send((&mut m, &mut g))?;
             }
             Ok(4) => {
                 let mut g = GSPAT::<NalgebraBackend, _, _>::new(foci, amps, c);
-                $autd.send(&mut m).send(&mut g)?;
+                $autd.send((&mut m, &mut g))?;
             }
             Ok(5) => {
                 let mut g = LM::<NalgebraBackend, _, _>::new(foci, amps, c);
-                $autd.send(&mut m).send(&mut g)?;
+                $autd.send((&mut m, &mut g))?;
             }
             Ok(6) => {
                 let mut g = Greedy::new(foci, amps, c);
-                $autd.send(&mut m).send(&mut g)?;
+                $autd.send((&mut m, &mut g))?;
             }
             _ => {
                 let mut g = GSPAT::<NalgebraBackend, _, _>::new(foci, amps, c);
-                $autd.send(&mut m).send(&mut g)?;
+                $autd.send((&mut m, &mut g))?;
             }
         };
     }};
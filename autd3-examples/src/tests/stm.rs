@@ -17,7 +17,7 @@ macro_rules! point_stm {
         use autd3::prelude::*;
 
         let mut silencer_config = SilencerConfig::none();
-        $autd.send(&mut silencer_config).flush()?;
+        $autd.send(&mut silencer_config)?;
 
         let center = $autd.geometry().center() + Vector3::new(0., 0., 150.0);
 
@@ -33,7 +33,7 @@ macro_rules! point_stm {
 
         let mut m = Static::new(0xFF);
 
-        $autd.send(&mut m).send(&mut stm)?;
+        $autd.send((&mut m, &mut stm))?;
     }};
 }
 
@@ -43,7 +43,7 @@ macro_rules! gain_stm {
         use autd3::prelude::*;
 
         let mut silencer_config = SilencerConfig::none();
-        $autd.send(&mut silencer_config).flush()?;
+        $autd.send(&mut silencer_config)?;
 
         let center = $autd.geometry().center() + Vector3::new(0., 0., 150.0);
 
@@ -61,6 +61,6 @@ macro_rules! gain_stm {
 
         let mut m = Static::new(0xFF);
 
-        $autd.send(&mut m).send(&mut stm)?;
+        $autd.send((&mut m, &mut stm))?;
     }};
 }
@@ -15,7 +15,7 @@
 macro_rules! trans_test {
     ($autd:ident) => {{
         let mut silencer_config = SilencerConfig::default();
-        $autd.send(&mut silencer_config).flush()?;
+        $autd.send(&mut silencer_config)?;
 
         let mut g = TransducerTest::new();
         g.set(0, 0., 1.0);
@@ -25,6 +25,6 @@ macro_rules! trans_test {
 
         let mut m = Static::new(0xFF);
 
-        $autd.send(&mut m).send(&mut g)?;
+        $autd.send((&mut m, &mut g))?;
     }};
 }
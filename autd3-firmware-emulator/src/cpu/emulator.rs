@@ -59,6 +59,10 @@ impl CPUEmulator {
         &self.fpga
     }
 
+    pub fn fpga_mut(&mut self) -> &mut FPGAEmulator {
+        &mut self.fpga
+    }
+
     pub fn send(&mut self, header: &GlobalHeader, body: &Body) {
         self.ecat_recv(header, body)
     }
@@ -114,6 +114,19 @@ impl FPGAEmulator {
         }
     }
 
+    /// Assert the thermal sensor as if the device overheated
+    pub fn assert_thermal_sensor(&mut self) {
+        self.controller_bram[ADDR_FPGA_INFO] |= 0x0001;
+    }
+
+    pub fn deassert_thermal_sensor(&mut self) {
+        self.controller_bram[ADDR_FPGA_INFO] &= !0x0001;
+    }
+
+    pub fn is_thermal_assert(&self) -> bool {
+        (self.controller_bram[ADDR_FPGA_INFO] & 0x0001) != 0
+    }
+
     pub fn is_legacy_mode(&self) -> bool {
         (self.controller_bram[ADDR_CTL_REG] & (1 << CTL_REG_LEGACY_MODE_BIT)) != 0
     }
@@ -194,7 +207,7 @@ impl FPGAEmulator {
             m.push((b & 0x00FF) as u8);
             m.push(((b >> 8) & 0x00FF) as u8);
         });
-        if cycle % 2 != 0 {
+        if cycle & 1 == 1 {
             let b = self.modulator_bram[(cycle + 1) >> 1];
             m.push((b & 0x00FF) as u8);
         }
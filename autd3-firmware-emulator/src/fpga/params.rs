@@ -19,7 +19,7 @@ pub const BRAM_SELECT_NORMAL: u16 = 0x2;
 pub const BRAM_SELECT_STM: u16 = 0x3;
 
 pub const ADDR_CTL_REG: usize = 0x0000;
-// pub const ADDR_FPGA_INFO: usize = 0x0001;
+pub const ADDR_FPGA_INFO: usize = 0x0001;
 pub const ADDR_EC_SYNC_CYCLE_TICKS: usize = 0x0010;
 // pub const ADDR_EC_SYNC_TIME_0: usize = ADDR_EC_SYNC_CYCLE_TICKS + 1;
 // pub const ADDR_EC_SYNC_TIME_1: usize = ADDR_EC_SYNC_CYCLE_TICKS + 2;
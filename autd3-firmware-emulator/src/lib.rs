@@ -34,6 +34,11 @@ impl Emulator {
         self.devices.iter_mut().for_each(|cpu| cpu.init());
     }
 
+    /// Reset the device as if it was power cycled
+    pub fn reset(&mut self, i: usize) {
+        self.devices[i] = CPUEmulator::new(i);
+    }
+
     pub fn send(&mut self, tx: &TxDatagram) {
         self.devices
             .iter_mut()
@@ -62,6 +67,10 @@ impl Emulator {
     pub fn fpga(&self, i: usize) -> &FPGAEmulator {
         &self.devices[i].fpga
     }
+
+    pub fn fpga_mut(&mut self, i: usize) -> &mut FPGAEmulator {
+        &mut self.devices[i].fpga
+    }
 }
 
 impl Default for Emulator {
@@ -13,9 +13,13 @@
 
 use std::f64::consts::PI;
 
-use crate::{constraint::Constraint, macros::propagate, Complex};
-use anyhow::Result;
+use crate::{
+    constraint::Constraint,
+    macros::{num_enabled_transducers, propagate},
+    Complex,
+};
 use autd3_core::{
+    error::AUTDInternalError,
     gain::{Gain, GainProps, IGain},
     geometry::{Geometry, Transducer, Vector3},
 };
@@ -55,7 +59,8 @@ impl<T: Transducer, C: Constraint> Greedy<T, C> {
 }
 
 impl<T: Transducer, C: Constraint> IGain<T> for Greedy<T, C> {
-    fn calc(&mut self, geometry: &Geometry<T>) -> Result<()> {
+    fn calc(&mut self, geometry: &Geometry<T>) -> Result<(), AUTDInternalError> {
+        num_enabled_transducers(geometry)?;
         let m = self.foci.len();
 
         let attenuation = geometry.attenuation;
@@ -81,10 +86,10 @@ impl<T: Transducer, C: Constraint> IGain<T> for Greedy<T, C> {
             }
         }
 
-        geometry.transducers().for_each(|trans| {
+        geometry.enabled_transducers().for_each(|trans| {
             let trans_dir = trans.z_direction();
             let mut min_idx = 0;
-            let mut min_v = std::f64::INFINITY;
+            let mut min_v = f64::INFINITY;
             for (idx, &phase) in self.phase_candidates.iter().enumerate() {
                 let wave_num = trans.wavenumber(sound_speed);
                 transfer_foci(
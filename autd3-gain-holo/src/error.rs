@@ -11,10 +11,19 @@
  *
  */
 
+use autd3_core::error::AUTDInternalError;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
 pub enum HoloError {
     #[error("Failed to solve linear system")]
     SolveFailed,
+    #[error("No transducer is enabled")]
+    NoEnabledTransducer,
+}
+
+impl From<HoloError> for AUTDInternalError {
+    fn from(e: HoloError) -> Self {
+        AUTDInternalError::Gain(Box::new(e))
+    }
 }
@@ -23,6 +23,7 @@ mod nls;
 pub use backend::*;
 pub use combinatorial::*;
 pub use constraint::*;
+pub use error::HoloError;
 pub use linear_synthesis::*;
 pub use matrix::*;
 pub use nls::*;
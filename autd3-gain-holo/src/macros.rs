@@ -11,11 +11,10 @@
  *
  */
 
-use crate::{Complex, MatrixXc};
+use crate::{error::HoloError, Complex, MatrixXc};
 use autd3_core::{
     geometry::{Geometry, Transducer, Vector3},
     utils::directivity_t4010a1 as directivity,
-    NUM_TRANS_IN_UNIT,
 };
 #[allow(unused)]
 use nalgebra::ComplexField;
@@ -37,19 +36,28 @@ pub fn propagate(
     r * Complex::new(0., phi).exp()
 }
 
+/// Return the number of enabled transducers, which is the number of columns of the propagation matrix
+///
+/// Return [HoloError::NoEnabledTransducer] if all transducers are disabled, since nothing can be solved.
+pub fn num_enabled_transducers<T: Transducer>(geometry: &Geometry<T>) -> Result<usize, HoloError> {
+    match geometry.num_enabled_transducers() {
+        0 => Err(HoloError::NoEnabledTransducer),
+        n => Ok(n),
+    }
+}
+
 pub fn generate_propagation_matrix<T: Transducer>(
     geometry: &Geometry<T>,
     foci: &[Vector3],
 ) -> MatrixXc {
     let m = foci.len();
-    let num_device = geometry.num_devices();
-    let num_trans = num_device * NUM_TRANS_IN_UNIT;
+    let num_trans = geometry.num_enabled_transducers();
     let sound_speed = geometry.sound_speed();
 
     MatrixXc::from_iterator(
         m,
         num_trans,
-        geometry.transducers().flat_map(|trans| {
+        geometry.enabled_transducers().flat_map(|trans| {
             foci.iter().map(move |&fp| {
                 let wavenum = trans.wavenumber(sound_speed);
                 propagate(
@@ -63,3 +71,116 @@ pub fn generate_propagation_matrix<T: Transducer>(
         }),
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use autd3_core::{
+        error::AUTDInternalError,
+        gain::Gain,
+        geometry::{GeometryBuilder, LegacyTransducer},
+    };
+
+    use super::*;
+    use crate::{Greedy, Naive, NalgebraBackend, Normalize, GS};
+
+    fn geometry() -> Geometry<LegacyTransducer> {
+        let mut geometry = GeometryBuilder::new().legacy_mode().build();
+        geometry.add_device(Vector3::zeros(), Vector3::zeros());
+        geometry.add_device(Vector3::new(192., 0., 0.), Vector3::zeros());
+        geometry.disable_transducer(3).unwrap();
+        geometry.devices_mut()[1].set_enabled(false);
+        geometry
+    }
+
+    fn focus(geometry: &Geometry<LegacyTransducer>) -> Vector3 {
+        geometry.devices()[0].center() + Vector3::new(0., 0., 150.)
+    }
+
+    #[test]
+    fn propagation_matrix_excludes_disabled_transducers() {
+        let geometry = geometry();
+        let foci = [focus(&geometry)];
+        let g = generate_propagation_matrix(&geometry, &foci);
+
+        assert_eq!(num_enabled_transducers(&geometry).unwrap(), 248);
+        assert_eq!(g.ncols(), 248);
+        geometry
+            .enabled_transducers()
+            .enumerate()
+            .for_each(|(j, tr)| {
+                let expected = propagate(
+                    tr.position(),
+                    tr.z_direction(),
+                    geometry.attenuation,
+                    tr.wavenumber(geometry.sound_speed()),
+                    foci[0],
+                );
+                assert_eq!(g[(0, j)], expected);
+            });
+    }
+
+    fn check_drives<G: Gain<LegacyTransducer>>(mut gain: G, geometry: &Geometry<LegacyTransducer>) {
+        gain.build(geometry).unwrap();
+        let drives = gain.drives();
+        assert_eq!(drives[3].amp, 0.);
+        assert!(drives[249..].iter().all(|d| d.amp == 0.));
+
+        // a single focus is made by compensating the propagation phase of each transducer, up to a global phase
+        let foci = [focus(geometry)];
+        let offsets = geometry
+            .enabled_transducers()
+            .map(|tr| {
+                let p = propagate(
+                    tr.position(),
+                    tr.z_direction(),
+                    geometry.attenuation,
+                    tr.wavenumber(geometry.sound_speed()),
+                    foci[0],
+                );
+                assert!(drives[tr.id()].amp > 0.);
+                drives[tr.id()].phase + p.argument() / (2.0 * PI)
+            })
+            .collect::<Vec<_>>();
+        offsets.iter().for_each(|o| {
+            let d = o - offsets[0];
+            assert!((d - d.round()).abs() < 1e-6);
+        });
+    }
+
+    #[test]
+    fn solved_drives_map_back_to_enabled_transducers() {
+        let geometry = geometry();
+        let foci = vec![focus(&geometry)];
+        check_drives(
+            Naive::<NalgebraBackend, _, _>::new(foci.clone(), vec![1.], Normalize {}),
+            &geometry,
+        );
+        check_drives(
+            GS::<NalgebraBackend, _, _>::new(foci, vec![1.], Normalize {}),
+            &geometry,
+        );
+    }
+
+    #[test]
+    fn all_disabled_is_an_error() {
+        let mut geometry = geometry();
+        geometry.devices_mut()[0].set_enabled(false);
+        let foci = vec![Vector3::new(0., 0., 150.)];
+
+        let is_no_enabled = |e: AUTDInternalError| match e {
+            AUTDInternalError::Gain(e) => matches!(
+                e.downcast_ref::<HoloError>(),
+                Some(HoloError::NoEnabledTransducer)
+            ),
+            _ => false,
+        };
+        let mut naive = Naive::<NalgebraBackend, _, _>::new(foci.clone(), vec![1.], Normalize {});
+        assert!(is_no_enabled(naive.build(&geometry).unwrap_err()));
+        let mut gs = GS::<NalgebraBackend, _, _>::new(foci.clone(), vec![1.], Normalize {});
+        assert!(is_no_enabled(gs.build(&geometry).unwrap_err()));
+        let mut greedy = Greedy::new(foci, vec![1.], Normalize {});
+        assert!(is_no_enabled(greedy.build(&geometry).unwrap_err()));
+    }
+}
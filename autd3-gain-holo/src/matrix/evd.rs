@@ -12,14 +12,15 @@
  */
 
 use crate::{
-    constraint::Constraint, error::HoloError, macros::generate_propagation_matrix, Backend,
-    Complex, MatrixXc, Transpose, VectorXc,
+    constraint::Constraint,
+    error::HoloError,
+    macros::{generate_propagation_matrix, num_enabled_transducers},
+    Backend, Complex, MatrixXc, Transpose, VectorXc,
 };
-use anyhow::Result;
 use autd3_core::{
+    error::AUTDInternalError,
     gain::{Gain, GainProps, IGain},
     geometry::{Geometry, Transducer, Vector3},
-    NUM_TRANS_IN_UNIT,
 };
 use autd3_traits::Gain;
 use nalgebra::ComplexField;
@@ -56,9 +57,9 @@ impl<B: Backend, T: Transducer, C: Constraint> EVD<B, T, C> {
 }
 
 impl<B: Backend, T: Transducer, C: Constraint> IGain<T> for EVD<B, T, C> {
-    fn calc(&mut self, geometry: &Geometry<T>) -> Result<()> {
+    fn calc(&mut self, geometry: &Geometry<T>) -> Result<(), AUTDInternalError> {
         let m = self.foci.len();
-        let n = geometry.num_devices() * NUM_TRANS_IN_UNIT;
+        let n = num_enabled_transducers(geometry)?;
 
         let g = generate_propagation_matrix(geometry, &self.foci);
 
@@ -127,12 +128,15 @@ impl<B: Backend, T: Transducer, C: Constraint> IGain<T> for EVD<B, T, C> {
         }
 
         let max_coefficient = B::max_coefficient_c(&gtf).abs();
-        geometry.transducers().for_each(|tr| {
-            let phase = gtf[tr.id()].argument() / (2.0 * PI) + 0.5;
-            let amp = self.constraint.convert(gtf[tr.id()].abs(), max_coefficient);
-            self.props.drives[tr.id()].amp = amp;
-            self.props.drives[tr.id()].phase = phase;
-        });
+        geometry
+            .enabled_transducers()
+            .enumerate()
+            .for_each(|(i, tr)| {
+                let phase = gtf[i].argument() / (2.0 * PI) + 0.5;
+                let amp = self.constraint.convert(gtf[i].abs(), max_coefficient);
+                self.props.drives[tr.id()].amp = amp;
+                self.props.drives[tr.id()].phase = phase;
+            });
 
         Ok(())
     }
@@ -12,14 +12,14 @@
  */
 
 use crate::{
-    constraint::Constraint, macros::generate_propagation_matrix, Backend, Complex, MatrixXc,
-    Transpose, VectorXc,
+    constraint::Constraint,
+    macros::{generate_propagation_matrix, num_enabled_transducers},
+    Backend, Complex, MatrixXc, Transpose, VectorXc,
 };
-use anyhow::Result;
 use autd3_core::{
+    error::AUTDInternalError,
     gain::{Gain, GainProps, IGain},
     geometry::{Geometry, Transducer, Vector3},
-    NUM_TRANS_IN_UNIT,
 };
 use autd3_traits::Gain;
 use nalgebra::ComplexField;
@@ -67,9 +67,9 @@ impl<B: Backend, T: Transducer, C: Constraint> SDP<B, T, C> {
     }
 }
 impl<B: Backend, T: Transducer, C: Constraint> IGain<T> for SDP<B, T, C> {
-    fn calc(&mut self, geometry: &Geometry<T>) -> Result<()> {
+    fn calc(&mut self, geometry: &Geometry<T>) -> Result<(), AUTDInternalError> {
         let m = self.foci.len();
-        let n = geometry.num_devices() * NUM_TRANS_IN_UNIT;
+        let n = num_enabled_transducers(geometry)?;
 
         let p = MatrixXc::from_diagonal(&VectorXc::from_iterator(
             m,
@@ -172,12 +172,15 @@ impl<B: Backend, T: Transducer, C: Constraint> IGain<T> for SDP<B, T, C> {
         );
 
         let max_coefficient = B::max_coefficient_c(&q).abs();
-        geometry.transducers().for_each(|tr| {
-            let phase = q[tr.id()].argument() / (2.0 * PI) + 0.5;
-            let amp = self.constraint.convert(q[tr.id()].abs(), max_coefficient);
-            self.props.drives[tr.id()].amp = amp;
-            self.props.drives[tr.id()].phase = phase;
-        });
+        geometry
+            .enabled_transducers()
+            .enumerate()
+            .for_each(|(i, tr)| {
+                let phase = q[i].argument() / (2.0 * PI) + 0.5;
+                let amp = self.constraint.convert(q[i].abs(), max_coefficient);
+                self.props.drives[tr.id()].amp = amp;
+                self.props.drives[tr.id()].phase = phase;
+            });
 
         Ok(())
     }
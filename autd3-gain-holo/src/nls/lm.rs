@@ -12,14 +12,15 @@
  */
 
 use crate::{
-    constraint::Constraint, error::HoloError, macros::generate_propagation_matrix, Backend,
-    Complex, MatrixX, MatrixXc, Transpose, VectorX, VectorXc,
+    constraint::Constraint,
+    error::HoloError,
+    macros::{generate_propagation_matrix, num_enabled_transducers},
+    Backend, Complex, MatrixX, MatrixXc, Transpose, VectorX, VectorXc,
 };
-use anyhow::Result;
 use autd3_core::{
+    error::AUTDInternalError,
     gain::{Gain, GainProps, IGain},
     geometry::{Geometry, Transducer, Vector3},
-    NUM_TRANS_IN_UNIT,
 };
 use autd3_traits::Gain;
 use nalgebra::ComplexField;
@@ -119,9 +120,9 @@ impl<B: Backend, T: Transducer, C: Constraint> LM<B, T, C> {
 impl<B: Backend, T: Transducer, C: Constraint> IGain<T> for LM<B, T, C> {
     #[allow(clippy::many_single_char_names)]
     #[allow(clippy::unnecessary_wraps)]
-    fn calc(&mut self, geometry: &Geometry<T>) -> Result<()> {
+    fn calc(&mut self, geometry: &Geometry<T>) -> Result<(), AUTDInternalError> {
         let m = self.foci.len();
-        let n = geometry.num_devices() * NUM_TRANS_IN_UNIT;
+        let n = num_enabled_transducers(geometry)?;
         let n_param = n + m;
 
         let bhb = Self::make_bhb(geometry, &self.amps, &self.foci, m, n);
@@ -220,12 +221,15 @@ impl<B: Backend, T: Transducer, C: Constraint> IGain<T> for LM<B, T, C> {
             }
         }
 
-        geometry.transducers().for_each(|tr| {
-            let phase = x[tr.id()].argument() / (2.0 * PI) + 0.5;
-            let amp = self.constraint.convert(1.0, 1.0);
-            self.props.drives[tr.id()].amp = amp;
-            self.props.drives[tr.id()].phase = phase;
-        });
+        geometry
+            .enabled_transducers()
+            .enumerate()
+            .for_each(|(i, tr)| {
+                let phase = x[i].argument() / (2.0 * PI) + 0.5;
+                let amp = self.constraint.convert(1.0, 1.0);
+                self.props.drives[tr.id()].amp = amp;
+                self.props.drives[tr.id()].phase = phase;
+            });
 
         Ok(())
     }
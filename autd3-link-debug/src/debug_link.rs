@@ -11,48 +11,73 @@
  *
  */
 
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
 use autd3_core::{
+    error::AUTDInternalError,
     geometry::{Geometry, Transducer},
-    link::Link,
+    link::{Link, LinkEvent},
     CPUControlFlags, RxDatagram, TxDatagram,
 };
 use autd3_firmware_emulator::Emulator;
 
 pub struct Debug {
-    emulator: Emulator,
+    emulator: Arc<Mutex<Emulator>>,
+    events: Arc<Mutex<Vec<LinkEvent>>>,
 }
 
 impl Debug {
     pub fn new() -> Self {
         Self {
-            emulator: Emulator::new(),
+            emulator: Arc::new(Mutex::new(Emulator::new())),
+            events: Arc::new(Mutex::new(vec![])),
         }
     }
+
+    /// Return the handle to the emulator behind this link
+    ///
+    /// The handle remains valid after the link is moved into a controller,
+    /// so that the state of the devices can be inspected.
+    pub fn emulator(&self) -> Arc<Mutex<Emulator>> {
+        self.emulator.clone()
+    }
+
+    /// Return the handle to the event queue of this link
+    ///
+    /// Events pushed to the queue are reported to the controller, so that link-level events can be simulated.
+    pub fn events(&self) -> Arc<Mutex<Vec<LinkEvent>>> {
+        self.events.clone()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Emulator> {
+        self.emulator.lock().unwrap_or_else(PoisonError::into_inner)
+    }
 }
 
 impl Link for Debug {
-    fn open<T: Transducer>(&mut self, geometry: &Geometry<T>) -> anyhow::Result<()> {
+    fn open<T: Transducer>(&mut self, geometry: &Geometry<T>) -> Result<(), AUTDInternalError> {
         log::info!("Open Debug link");
 
-        self.emulator.init(geometry.num_devices());
+        self.lock().init(geometry.num_devices());
         log::info!("Initialize emulator");
 
         Ok(())
     }
 
-    fn close(&mut self) -> anyhow::Result<()> {
+    fn close(&mut self) -> Result<(), AUTDInternalError> {
         log::info!("Close Debug link");
         Ok(())
     }
 
-    fn send(&mut self, tx: &TxDatagram) -> anyhow::Result<bool> {
+    fn send(&mut self, tx: &TxDatagram) -> Result<bool, AUTDInternalError> {
         log::info!("Send data");
         log::info!("\tCPU Flag: {:?}", tx.header().cpu_flag);
         log::info!("\tFPGA Flag: {:?}", tx.header().fpga_flag);
 
-        self.emulator.send(tx);
+        let mut emulator = self.lock();
+        emulator.send(tx);
 
-        self.emulator.cpus().iter().for_each(|cpu| {
+        emulator.cpus().iter().for_each(|cpu| {
             log::info!("Status: {}", cpu.id());
             let fpga = cpu.fpga();
             if fpga.is_stm_mode() {
@@ -130,10 +155,10 @@ impl Link for Debug {
         Ok(true)
     }
 
-    fn receive(&mut self, rx: &mut RxDatagram) -> anyhow::Result<bool> {
+    fn receive(&mut self, rx: &mut RxDatagram) -> Result<bool, AUTDInternalError> {
         log::info!("Receive data");
 
-        self.emulator.read(rx);
+        self.lock().read(rx);
 
         Ok(true)
     }
@@ -141,6 +166,14 @@ impl Link for Debug {
     fn is_open(&self) -> bool {
         true
     }
+
+    fn poll_events(&mut self) -> Vec<LinkEvent> {
+        self.events
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .drain(..)
+            .collect()
+    }
 }
 
 impl Default for Debug {
@@ -148,3 +181,34 @@ impl Default for Debug {
         Self::new()
     }
 }
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl autd3_core::link::AsyncLink for Debug {
+    async fn open<T: Transducer + Sync>(
+        &mut self,
+        geometry: &Geometry<T>,
+    ) -> Result<(), AUTDInternalError> {
+        Link::open(self, geometry)
+    }
+
+    async fn close(&mut self) -> Result<(), AUTDInternalError> {
+        Link::close(self)
+    }
+
+    async fn send(&mut self, tx: &TxDatagram) -> Result<bool, AUTDInternalError> {
+        Link::send(self, tx)
+    }
+
+    async fn receive(&mut self, rx: &mut RxDatagram) -> Result<bool, AUTDInternalError> {
+        Link::receive(self, rx)
+    }
+
+    fn is_open(&self) -> bool {
+        Link::is_open(self)
+    }
+
+    fn poll_events(&mut self) -> Vec<LinkEvent> {
+        Link::poll_events(self)
+    }
+}
@@ -14,6 +14,7 @@
 use std::net::UdpSocket;
 
 use autd3_core::{
+    error::AUTDInternalError,
     geometry::{Geometry, Transducer},
     link::Link,
     CPUControlFlags, FPGAControlFlags, RxDatagram, TxDatagram, MSG_EMU_GEOMETRY_SET,
@@ -36,8 +37,12 @@ impl Emulator {
     }
 }
 
+fn io_error(e: std::io::Error) -> AUTDInternalError {
+    AUTDInternalError::Link(Box::new(e))
+}
+
 impl Link for Emulator {
-    fn open<T: Transducer>(&mut self, geometry: &Geometry<T>) -> anyhow::Result<()> {
+    fn open<T: Transducer>(&mut self, geometry: &Geometry<T>) -> Result<(), AUTDInternalError> {
         let mut geometry_buf = TxDatagram::new(geometry.num_devices());
         geometry_buf.num_bodies = geometry.num_devices();
 
@@ -70,28 +75,31 @@ impl Link for Emulator {
                 }
             });
 
-        let socket = UdpSocket::bind("0.0.0.0:8080")?;
+        let socket = UdpSocket::bind("0.0.0.0:8080").map_err(io_error)?;
         let remote_addr = format!("127.0.0.1:{}", self.port);
-        socket.connect(remote_addr)?;
-        socket.send(geometry_buf.data())?;
+        socket.connect(remote_addr).map_err(io_error)?;
+        socket.send(geometry_buf.data()).map_err(io_error)?;
         self.socket = Some(socket);
         Ok(())
     }
 
-    fn close(&mut self) -> anyhow::Result<()> {
+    fn close(&mut self) -> Result<(), AUTDInternalError> {
         self.socket = None;
         Ok(())
     }
 
-    fn send(&mut self, tx: &TxDatagram) -> anyhow::Result<bool> {
+    fn send(&mut self, tx: &TxDatagram) -> Result<bool, AUTDInternalError> {
         if let Some(socket) = &self.socket {
-            socket.try_clone()?.send(tx.data())?;
+            socket
+                .try_clone()
+                .and_then(|s| s.send(tx.data()))
+                .map_err(io_error)?;
             self.last_msg_id = tx.header().msg_id;
         }
         Ok(true)
     }
 
-    fn receive(&mut self, rx: &mut RxDatagram) -> anyhow::Result<bool> {
+    fn receive(&mut self, rx: &mut RxDatagram) -> Result<bool, AUTDInternalError> {
         for r in rx.messages_mut() {
             r.msg_id = self.last_msg_id;
         }
@@ -113,3 +121,30 @@ impl Link for Emulator {
         self.socket.is_some()
     }
 }
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl autd3_core::link::AsyncLink for Emulator {
+    async fn open<T: Transducer + Sync>(
+        &mut self,
+        geometry: &Geometry<T>,
+    ) -> Result<(), AUTDInternalError> {
+        Link::open(self, geometry)
+    }
+
+    async fn close(&mut self) -> Result<(), AUTDInternalError> {
+        Link::close(self)
+    }
+
+    async fn send(&mut self, tx: &TxDatagram) -> Result<bool, AUTDInternalError> {
+        Link::send(self, tx)
+    }
+
+    async fn receive(&mut self, rx: &mut RxDatagram) -> Result<bool, AUTDInternalError> {
+        Link::receive(self, rx)
+    }
+
+    fn is_open(&self) -> bool {
+        Link::is_open(self)
+    }
+}
@@ -13,13 +13,22 @@
 
 use std::fmt::Write as _;
 
+use autd3_core::link::LinkEvent;
+use crossbeam_channel::Sender;
+
 use crate::native_methods::*;
 
 pub struct EcatErrorHandler<F: Fn(&str)> {
     pub error_handle: Option<F>,
+    pub events: Sender<LinkEvent>,
 }
 
 impl<F: Fn(&str)> EcatErrorHandler<F> {
+    fn send(&self, event: LinkEvent) {
+        // Drop the event rather than block the EtherCAT thread if the buffer is full
+        let _ = self.events.try_send(event);
+    }
+
     pub fn handle(&self) -> bool {
         unsafe {
             ec_group[0].docheckstate = 0;
@@ -36,12 +45,12 @@ impl<F: Fn(&str)> EcatErrorHandler<F> {
                         if slave.state
                             == ec_state_EC_STATE_SAFE_OP as u16 + ec_state_EC_STATE_ERROR as u16
                         {
-                            writeln!(
-                                msg,
+                            let err = format!(
                                 "ERROR : slave {} is in SAFE_OP + ERROR, attempting ack",
                                 i
-                            )
-                            .unwrap();
+                            );
+                            writeln!(msg, "{}", err).unwrap();
+                            self.send(LinkEvent::Error(err));
                             slave.state =
                                 ec_state_EC_STATE_SAFE_OP as u16 + ec_state_EC_STATE_ACK as u16;
                             ec_writestate(i as _);
@@ -58,6 +67,7 @@ impl<F: Fn(&str)> EcatErrorHandler<F> {
                             if ec_reconfig_slave(i as _, 500) != 0 {
                                 slave.islost = 0;
                                 writeln!(msg, "MESSAGE : slave {} reconfigured", i).unwrap();
+                                self.send(LinkEvent::DeviceRecovered(i - 1));
                             }
                         } else if slave.islost == 0 {
                             ec_statecheck(
@@ -67,7 +77,10 @@ impl<F: Fn(&str)> EcatErrorHandler<F> {
                             );
                             if slave.state == ec_state_EC_STATE_NONE as _ {
                                 slave.islost = 1;
-                                writeln!(msg, "ERROR : slave {} lost", i).unwrap();
+                                let err = format!("ERROR : slave {} lost", i);
+                                writeln!(msg, "{}", err).unwrap();
+                                self.send(LinkEvent::DeviceLost(i - 1));
+                                self.send(LinkEvent::Error(err));
                             }
                         }
                     }
@@ -76,10 +89,12 @@ impl<F: Fn(&str)> EcatErrorHandler<F> {
                             if ec_recover_slave(i as _, 500) != 0 {
                                 slave.islost = 0;
                                 writeln!(msg, "MESSAGE : slave {} recovered", i).unwrap();
+                                self.send(LinkEvent::DeviceRecovered(i - 1));
                             }
                         } else {
                             slave.islost = 0;
                             writeln!(msg, "MESSAGE : slave {} found", i).unwrap();
+                            self.send(LinkEvent::DeviceRecovered(i - 1));
                         }
                     }
                 });
@@ -27,9 +27,10 @@ use crate::{iomap::IOMap, native_methods::*};
 
 use super::{error_handler::EcatErrorHandler, utils::*};
 
+pub use autd3_core::timer::{HighPrecisionWaiter, NormalWaiter};
+
+/// The cycle is waited with the monotonic clock regardless of the waiter
 pub trait Waiter {}
-pub struct NormalWaiter {}
-pub struct HighPrecisionWaiter {}
 impl Waiter for NormalWaiter {}
 impl Waiter for HighPrecisionWaiter {}
 
@@ -85,11 +85,12 @@ fn add_timespec(ts: &mut timespec, addtime: i64) {
     }
 }
 
+pub use autd3_core::timer::{HighPrecisionWaiter, NormalWaiter};
+
+/// Wait for the absolute time of the system clock used by SOEM
 pub trait Waiter {
     fn timed_wait(abs_time: &timespec);
 }
-pub struct NormalWaiter {}
-pub struct HighPrecisionWaiter {}
 
 impl Waiter for NormalWaiter {
     fn timed_wait(abs_time: &timespec) {
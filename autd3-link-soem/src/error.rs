@@ -11,6 +11,7 @@
  *
  */
 
+use autd3_core::error::AUTDInternalError;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -24,3 +25,9 @@ pub enum SOEMError {
     #[error("One ore more slaves are not responding")]
     NotResponding,
 }
+
+impl From<SOEMError> for AUTDInternalError {
+    fn from(e: SOEMError) -> Self {
+        AUTDInternalError::Link(Box::new(e))
+    }
+}
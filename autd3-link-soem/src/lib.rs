@@ -21,6 +21,7 @@ mod native_methods;
 mod sync_mode;
 
 pub use config::Config;
+pub use error::SOEMError;
 pub use ethernet_adapters::EthernetAdapters;
 pub use link_soem::SOEM;
 pub use sync_mode::SyncMode;
\ No newline at end of file
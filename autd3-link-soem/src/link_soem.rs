@@ -21,14 +21,13 @@ use std::{
     usize,
 };
 
-use anyhow::Result;
-use crossbeam_channel::{bounded, Sender};
+use crossbeam_channel::{bounded, Receiver, Sender};
 use libc::c_void;
 
 use autd3_core::{
     error::AUTDInternalError,
     geometry::{Geometry, Transducer},
-    link::Link,
+    link::{Link, LinkEvent},
     RxDatagram, TxDatagram, EC_CYCLE_TIME_BASE_NANO_SEC,
 };
 
@@ -41,6 +40,8 @@ use crate::{
 };
 
 const SEND_BUF_SIZE: usize = 32;
+// Events are dropped if nobody polls them and the buffer is full
+const EVENT_BUF_SIZE: usize = 64;
 
 pub struct SOEM<F: Fn(&str) + Send> {
     ecatth_handle: Option<JoinHandle<()>>,
@@ -53,12 +54,15 @@ pub struct SOEM<F: Fn(&str) + Send> {
     rx: Arc<Mutex<RxDatagram>>,
     ec_sync0_cycle_time_ns: u32,
     ec_send_cycle_time_ns: u32,
+    event_sender: Sender<LinkEvent>,
+    event_receiver: Receiver<LinkEvent>,
 }
 
 impl<F: Fn(&str) + Send> SOEM<F> {
     pub fn new(config: Config, error_handle: F) -> Self {
         let ec_send_cycle_time_ns = EC_CYCLE_TIME_BASE_NANO_SEC * config.send_cycle as u32;
         let ec_sync0_cycle_time_ns = EC_CYCLE_TIME_BASE_NANO_SEC * config.sync0_cycle as u32;
+        let (event_sender, event_receiver) = bounded(EVENT_BUF_SIZE);
         Self {
             ecatth_handle: None,
             error_handle: Some(error_handle),
@@ -70,11 +74,13 @@ impl<F: Fn(&str) + Send> SOEM<F> {
             config,
             ec_sync0_cycle_time_ns,
             ec_send_cycle_time_ns,
+            event_sender,
+            event_receiver,
         }
     }
 }
 
-fn lookup_autd() -> anyhow::Result<String> {
+fn lookup_autd() -> Result<String, SOEMError> {
     let adapters: EthernetAdapters = Default::default();
 
     if let Some(adapter) = adapters.into_iter().find(|adapter| unsafe {
@@ -102,7 +108,7 @@ fn lookup_autd() -> anyhow::Result<String> {
     }) {
         Ok(adapter.name.to_owned())
     } else {
-        Err(SOEMError::NoDeviceFound.into())
+        Err(SOEMError::NoDeviceFound)
     }
 }
 
@@ -113,7 +119,7 @@ unsafe extern "C" fn dc_config(context: *mut ecx_contextt, slave: u16) -> i32 {
 }
 
 impl<F: 'static + Fn(&str) + Send> Link for SOEM<F> {
-    fn open<T: Transducer>(&mut self, geometry: &Geometry<T>) -> anyhow::Result<()> {
+    fn open<T: Transducer>(&mut self, geometry: &Geometry<T>) -> Result<(), AUTDInternalError> {
         let dev_num = geometry.num_devices() as u16;
 
         self.rx = Arc::new(Mutex::new(RxDatagram::new(geometry.num_devices())));
@@ -185,8 +191,12 @@ impl<F: 'static + Fn(&str) + Send> Link for SOEM<F> {
             let error_handle = self.error_handle.take();
             let thread_running = self.thread_running.clone();
             let is_high_precision = self.config.high_precision_timer;
+            let events = self.event_sender.clone();
             self.ecatth_handle = Some(std::thread::spawn(move || {
-                let error_handler = EcatErrorHandler { error_handle };
+                let error_handler = EcatErrorHandler {
+                    error_handle,
+                    events,
+                };
                 if is_high_precision {
                     let mut callback = EcatThreadHandler::<_, HighPrecisionWaiter>::new(
                         io_map,
@@ -237,7 +247,7 @@ impl<F: 'static + Fn(&str) + Send> Link for SOEM<F> {
         Ok(())
     }
 
-    fn close(&mut self) -> Result<()> {
+    fn close(&mut self) -> Result<(), AUTDInternalError> {
         if !self.is_open {
             return Ok(());
         }
@@ -281,17 +291,21 @@ impl<F: 'static + Fn(&str) + Send> Link for SOEM<F> {
         Ok(())
     }
 
-    fn send(&mut self, tx: &TxDatagram) -> Result<bool> {
+    fn send(&mut self, tx: &TxDatagram) -> Result<bool, AUTDInternalError> {
         let buf = tx.clone();
 
-        self.sender.as_mut().unwrap().send(buf)?;
+        self.sender
+            .as_mut()
+            .unwrap()
+            .send(buf)
+            .map_err(|_| AUTDInternalError::LinkClosed)?;
 
         Ok(true)
     }
 
-    fn receive(&mut self, rx: &mut RxDatagram) -> Result<bool> {
+    fn receive(&mut self, rx: &mut RxDatagram) -> Result<bool, AUTDInternalError> {
         if !self.is_open {
-            return Err(AUTDInternalError::LinkClosed.into());
+            return Err(AUTDInternalError::LinkClosed);
         }
 
         rx.copy_from(&self.rx.lock().unwrap());
@@ -302,4 +316,8 @@ impl<F: 'static + Fn(&str) + Send> Link for SOEM<F> {
     fn is_open(&self) -> bool {
         self.is_open
     }
+
+    fn poll_events(&mut self) -> Vec<LinkEvent> {
+        self.event_receiver.try_iter().collect()
+    }
 }
@@ -11,6 +11,7 @@
  *
  */
 
+use autd3_core::error::AUTDInternalError;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -24,3 +25,9 @@ pub enum AdsError {
     #[error("Failed to read data: {0}")]
     ReadData(i32),
 }
+
+impl From<AdsError> for AUTDInternalError {
+    fn from(e: AdsError) -> Self {
+        AUTDInternalError::Link(Box::new(e))
+    }
+}
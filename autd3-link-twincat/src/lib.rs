@@ -15,4 +15,5 @@ mod error;
 mod native_methods;
 mod twincat_link;
 
+pub use error::AdsError;
 pub use twincat_link::TwinCAT;
@@ -26,7 +26,7 @@ fn impl_modulation_macro(ast: &syn::DeriveInput) -> TokenStream {
     let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
     let gen = quote! {
         impl #impl_generics Modulation for #name #ty_generics #where_clause {
-            fn build(&mut self) -> anyhow::Result<()> {
+            fn build(&mut self) -> Result<(), autd3_core::error::AUTDInternalError> {
                 if self.props.built {
                     return Ok(());
                 }
@@ -41,7 +41,7 @@ fn impl_modulation_macro(ast: &syn::DeriveInput) -> TokenStream {
                 Ok(())
             }
 
-            fn rebuild(&mut self) -> anyhow::Result<()>{
+            fn rebuild(&mut self) -> Result<(), autd3_core::error::AUTDInternalError>{
                 self.props.built = false;
                 self.build()
             }
@@ -60,7 +60,7 @@ fn impl_modulation_macro(ast: &syn::DeriveInput) -> TokenStream {
         }
 
         impl #impl_generics autd3_core::interface::DatagramHeader for #name #ty_generics #where_clause {
-            fn init(&mut self) -> anyhow::Result<()> {
+            fn init(&mut self) -> Result<(), autd3_core::error::AUTDInternalError> {
                 self.build()?;
                 self.props.sent = 0;
                 Ok(())
@@ -70,7 +70,7 @@ fn impl_modulation_macro(ast: &syn::DeriveInput) -> TokenStream {
                 &mut self,
                 msg_id: u8,
                 tx: &mut autd3_core::TxDatagram,
-            ) -> anyhow::Result<()> {
+            ) -> Result<(), autd3_core::error::AUTDInternalError> {
                 let is_first_frame = self.props.sent == 0;
                 let max_size = if is_first_frame {autd3_core::MOD_HEAD_DATA_SIZE} else {autd3_core::MOD_BODY_DATA_SIZE};
                 let mod_size = (self.buffer().len() - self.props.sent).min(max_size);
@@ -88,24 +88,20 @@ fn impl_modulation_macro(ast: &syn::DeriveInput) -> TokenStream {
         }
 
         impl <T: autd3_core::geometry::Transducer> autd3_core::interface::Sendable<T> for #name #ty_generics #where_clause {
-            type H = autd3_core::interface::Filled;
-            type B = autd3_core::interface::Empty;
+            type H = Self;
+            type B = autd3_core::interface::NullBody;
 
-            fn init(&mut self) -> anyhow::Result<()> {
-                autd3_core::interface::DatagramHeader::init(self)
+            fn operation(self) -> (Self::H, Self::B) {
+                (self, autd3_core::interface::NullBody::new())
             }
+        }
 
-            fn pack(
-                &mut self,
-                msg_id: u8,
-                _geometry: &autd3_core::geometry::Geometry<T>,
-                tx: &mut autd3_core::TxDatagram,
-            ) -> anyhow::Result<()> {
-                autd3_core::interface::DatagramHeader::pack(self, msg_id, tx)
-            }
+        impl <T: autd3_core::geometry::Transducer> autd3_core::interface::Sendable<T> for &mut #name #ty_generics #where_clause {
+            type H = Self;
+            type B = autd3_core::interface::NullBody;
 
-            fn is_finished(&self) -> bool {
-                autd3_core::interface::DatagramHeader::is_finished(self)
+            fn operation(self) -> (Self::H, Self::B) {
+                (self, autd3_core::interface::NullBody::new())
             }
         }
     };
@@ -124,7 +120,7 @@ fn impl_gain_macro(ast: syn::DeriveInput) -> TokenStream {
     let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
     let gen = quote! {
         impl #impl_generics Gain<T> for #name #ty_generics #where_clause {
-            fn build(&mut self, geometry: &Geometry<T>) -> anyhow::Result<()> {
+            fn build(&mut self, geometry: &Geometry<T>) -> Result<(), autd3_core::error::AUTDInternalError> {
                 if self.props.built {
                     return Ok(());
                 }
@@ -138,7 +134,7 @@ fn impl_gain_macro(ast: syn::DeriveInput) -> TokenStream {
                 Ok(())
             }
 
-            fn rebuild(&mut self, geometry: &Geometry<T>) -> anyhow::Result<()> {
+            fn rebuild(&mut self, geometry: &Geometry<T>) -> Result<(), autd3_core::error::AUTDInternalError> {
                 self.props.built = false;
                 self.build(geometry)
             }
@@ -157,7 +153,7 @@ fn impl_gain_macro(ast: syn::DeriveInput) -> TokenStream {
         }
 
         impl #impl_generics autd3_core::interface::DatagramBody<T> for #name #ty_generics #where_clause {
-            fn init(&mut self) -> anyhow::Result<()> {
+            fn init(&mut self) -> Result<(), autd3_core::error::AUTDInternalError> {
                 self.props.phase_sent = false;
                 self.props.duty_sent = false;
                 Ok(())
@@ -167,13 +163,13 @@ fn impl_gain_macro(ast: syn::DeriveInput) -> TokenStream {
                 &mut self,
                 geometry: &autd3_core::geometry::Geometry<T>,
                 tx: &mut autd3_core::TxDatagram,
-            ) -> anyhow::Result<()> {
+            ) -> Result<(), autd3_core::error::AUTDInternalError> {
                 self.props.pack_head(tx);
                 if self.is_finished() {
                     return Ok(());
                 }
                 self.build(geometry)?;
-                self.props.pack_body(tx)?;
+                self.props.pack_body(geometry, tx)?;
                 Ok(())
             }
 
@@ -184,24 +180,20 @@ fn impl_gain_macro(ast: syn::DeriveInput) -> TokenStream {
 
 
         impl #impl_generics autd3_core::interface::Sendable<T> for #name #ty_generics #where_clause {
-            type H = autd3_core::interface::Empty;
-            type B = autd3_core::interface::Filled;
+            type H = autd3_core::interface::NullHeader;
+            type B = Self;
 
-            fn init(&mut self) -> anyhow::Result<()> {
-                autd3_core::interface::DatagramBody::<T>::init(self)
+            fn operation(self) -> (Self::H, Self::B) {
+                (autd3_core::interface::NullHeader::new(), self)
             }
+        }
 
-            fn pack(
-                &mut self,
-                msg_id: u8,
-                geometry: &autd3_core::geometry::Geometry<T>,
-                tx: &mut autd3_core::TxDatagram,
-            ) -> anyhow::Result<()> {
-                autd3_core::interface::DatagramBody::<T>::pack(self, geometry, tx)
-            }
+        impl #impl_generics autd3_core::interface::Sendable<T> for &mut #name #ty_generics #where_clause {
+            type H = autd3_core::interface::NullHeader;
+            type B = Self;
 
-            fn is_finished(&self) -> bool {
-                autd3_core::interface::DatagramBody::<T>::is_finished(self)
+            fn operation(self) -> (Self::H, Self::B) {
+                (autd3_core::interface::NullHeader::new(), self)
             }
         }
     };
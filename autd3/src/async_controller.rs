@@ -0,0 +1,695 @@
+/*
+ * File: async_controller.rs
+ * Project: src
+ * Created Date: 18/10/2026
+ * Author: Shun Suzuki
+ * -----
+ * Last Modified: 18/10/2026
+ * Modified By: Shun Suzuki (suzuki@hapis.k.u-tokyo.ac.jp)
+ * -----
+ * Copyright (c) 2026 Shun Suzuki. All rights reserved.
+ *
+ */
+
+use std::{
+    sync::{mpsc::Receiver, Arc},
+    time::{Duration, Instant},
+};
+
+use autd3_core::{
+    geometry::{Geometry, Transducer},
+    interface::Sendable,
+    link::AsyncLink,
+    silencer_config::SilencerConfig,
+    FPGAInfo, FirmwareInfo, TxDatagram,
+};
+use tokio::sync::Mutex;
+
+use crate::{
+    engine::{self, Engine},
+    error::AUTDError,
+    event::ControllerEvent,
+    policy::{DropBehavior, SendPolicy, SyncPolicy},
+    prelude::Null,
+    progress::{CancellationToken, Progress},
+    report::{AckTracker, SendReport},
+    state::DeviceState,
+    validate::Validation,
+    watchdog::Watchdog,
+};
+
+/// Asynchronous counterpart of [Controller](crate::Controller)
+///
+/// Every wait between EtherCAT cycles yields to the executor instead of blocking the thread.
+///
+/// # Example
+///
+/// ```
+/// use autd3::prelude::*;
+/// use autd3_link_debug::Debug;
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() -> Result<(), AUTDError> {
+/// let mut geometry = GeometryBuilder::new().legacy_mode().build();
+/// geometry.add_device(Vector3::zeros(), Vector3::zeros());
+///
+/// let mut autd = AsyncController::open(geometry, Debug::new()).await?;
+///
+/// autd.clear().await?;
+///
+/// let mut m = Sine::new(150);
+/// let mut g = Focus::new(autd.geometry().center() + Vector3::new(0., 0., 150.));
+/// assert!(autd.send((&mut m, &mut g)).await?.is_success());
+///
+/// autd.close().await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct AsyncController<L: AsyncLink + 'static, T: Transducer> {
+    link: Arc<Mutex<L>>,
+    engine: Engine<T>,
+    pub send_policy: SendPolicy,
+    pub send_interval: usize,
+    pub force_fan: bool,
+    pub reads_fpga_info: bool,
+    /// Behavior when dropped without calling [AsyncController::close]
+    ///
+    /// The frames are sent by a task spawned on the current tokio runtime, without waiting for acknowledgements.
+    pub drop_behavior: DropBehavior,
+    pub sync_policy: SyncPolicy,
+    /// Restore the devices automatically when the link reports recovery of devices
+    pub auto_restore: bool,
+    /// Skip resending modulation, silencer config, modulation delay and synchronization which the devices already hold
+    ///
+    /// Data is regarded as held only if all devices acknowledged it, so this has no effect without ack checking in `send_policy`.
+    /// Default is false.
+    pub deduplicates: bool,
+    closed: bool,
+    watchdog: Option<Watchdog>,
+}
+
+impl<L: AsyncLink + 'static, T: Transducer + Send + Sync> AsyncController<L, T> {
+    pub async fn open(geometry: Geometry<T>, link: L) -> Result<AsyncController<L, T>, AUTDError> {
+        let mut link = link;
+        link.open(&geometry).await?;
+        Ok(AsyncController {
+            link: Arc::new(Mutex::new(link)),
+            engine: Engine::new(geometry),
+            send_policy: SendPolicy::default(),
+            send_interval: 1,
+            force_fan: false,
+            reads_fpga_info: false,
+            drop_behavior: DropBehavior::default(),
+            sync_policy: SyncPolicy::default(),
+            auto_restore: true,
+            deduplicates: false,
+            closed: false,
+            watchdog: None,
+        })
+    }
+
+    pub fn geometry(&self) -> &Geometry<T> {
+        &self.engine.geometry
+    }
+
+    /// Return mutable reference to the geometry
+    ///
+    /// Devices must not be added after opening the controller.
+    pub fn geometry_mut(&mut self) -> &mut Geometry<T> {
+        &mut self.engine.geometry
+    }
+
+    /// Return true if the transducer cycles are the same as the last synchronized ones
+    pub fn is_synchronized(&self) -> bool {
+        self.engine.is_synchronized()
+    }
+
+    /// Return the last confirmed state of each device
+    ///
+    /// The state is None until the device acknowledges clear.
+    pub fn state(&self) -> &[Option<DeviceState>] {
+        self.engine.state.devices()
+    }
+
+    /// Return a receiver of events occurred in the controller and the link
+    ///
+    /// Link events are received when sending data or calling [AsyncController::handle_link_events].
+    pub fn subscribe(&mut self) -> Receiver<ControllerEvent> {
+        self.engine.events.subscribe()
+    }
+
+    /// Send header and body to the devices
+    ///
+    /// If either of header or body finishes earlier than the other, the remaining frames are filled with null data.
+    ///
+    /// # Arguments
+    ///
+    /// * `s` - Header, body, or tuple of header and body
+    ///
+    pub async fn send<S: Sendable<T>>(&mut self, s: S) -> Result<SendReport, AUTDError> {
+        self.send_guarded(s, true).await
+    }
+
+    /// Send header and body, checking synchronization according to `sync_policy` if `guards_sync` is true
+    async fn send_guarded<S: Sendable<T>>(
+        &mut self,
+        s: S,
+        guards_sync: bool,
+    ) -> Result<SendReport, AUTDError> {
+        let restored = self.handle_link_events().await?;
+        let (frames, fingerprints) = match self.engine.pack(s, self.deduplicates)? {
+            Some(packed) => packed,
+            None => return Ok(restored),
+        };
+        let (report, _) = self.send_frames(&frames, guards_sync, |_| true).await?;
+        self.engine.record_fingerprints(fingerprints, &report);
+        Ok(restored.merge(report))
+    }
+
+    /// Pack data into scratch frames to find errors before sending, without touching the link
+    ///
+    /// See [Controller::validate](crate::Controller::validate).
+    ///
+    /// # Arguments
+    ///
+    /// * `s` - Header, body, or tuple of header and body
+    ///
+    pub fn validate<S: Sendable<T>>(&self, s: S) -> Validation {
+        self.engine.validate(s, self.interval(), self.sync_policy)
+    }
+
+    /// Send header and body to the devices with progress report and cancellation
+    ///
+    /// The token is checked between frames. If cancelled, Null gain is sent to stop STM and output,
+    /// and [AUTDError::Cancelled] is returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `s` - Header, body, or tuple of header and body
+    /// * `token` - Cancellation token
+    /// * `progress` - Callback called after each frame of the data is sent, not counting frames for synchronization
+    ///
+    pub async fn send_with_progress<S: Sendable<T>, F: FnMut(Progress)>(
+        &mut self,
+        s: S,
+        token: &CancellationToken,
+        mut progress: F,
+    ) -> Result<SendReport, AUTDError> {
+        if token.is_cancelled() {
+            return Err(AUTDError::Cancelled(self.engine.new_report()));
+        }
+
+        let restored = self.handle_link_events().await?;
+        let (frames, fingerprints) = match self.engine.pack(s, self.deduplicates)? {
+            Some(packed) => packed,
+            None => return Ok(restored),
+        };
+        let total = frames.len();
+
+        let (report, sent) = self
+            .send_frames(&frames, true, |sent| {
+                progress(Progress { sent, total });
+                !token.is_cancelled()
+            })
+            .await?;
+        if sent == total {
+            self.engine.record_fingerprints(fingerprints, &report);
+            return Ok(restored.merge(report));
+        }
+
+        let report = restored.merge(report);
+
+        let report = report.merge(self.send_guarded(Null::<T>::new(), false).await?);
+        Err(AUTDError::Cancelled(report))
+    }
+
+    /// Clear all data
+    pub async fn clear(&mut self) -> Result<SendReport, AUTDError> {
+        self.engine.load_clear();
+        let mut report = self.engine.new_report();
+        self.send_frame(&mut report).await?;
+        Ok(report)
+    }
+
+    /// Synchronize transducer cycles of the devices
+    ///
+    /// Nothing is sent if `deduplicates` is true and the devices are already synchronized with the current cycles.
+    /// If any device does not acknowledge, the controller is regarded as not synchronized and [AUTDError::SendFailed] is returned.
+    pub async fn synchronize(&mut self) -> Result<SendReport, AUTDError> {
+        if self.deduplicates && self.is_synchronized() {
+            return Ok(self.engine.new_report());
+        }
+
+        let cycles = self
+            .engine
+            .load_sync(self.force_fan, self.reads_fpga_info)?;
+        let mut report = self.engine.new_report();
+        self.send_frame(&mut report).await?;
+        self.engine.confirm_sync(cycles, report)
+    }
+
+    /// Forget the data which the devices hold, so that the next send always sends it
+    pub fn invalidate_cache(&mut self) {
+        self.engine.invalidate_cache();
+    }
+
+    /// Return firmware information of the devices
+    pub async fn firmware_infos(&mut self) -> Result<Vec<FirmwareInfo>, AUTDError> {
+        autd3_core::cpu_version(&mut self.engine.tx_buf);
+        let cpu_versions = self.read_acks().await?;
+
+        autd3_core::fpga_version(&mut self.engine.tx_buf);
+        let fpga_versions = self.read_acks().await?;
+
+        autd3_core::fpga_functions(&mut self.engine.tx_buf);
+        let fpga_functions = self.read_acks().await?;
+
+        Ok(self
+            .engine
+            .firmware_infos(&cpu_versions, &fpga_versions, &fpga_functions))
+    }
+
+    /// Resend the last sent data to restore the devices
+    ///
+    /// This is called automatically before sending if `auto_restore` is true and the link reports recovery of devices.
+    pub async fn restore(&mut self) -> Result<SendReport, AUTDError> {
+        let mut report = self.engine.new_report();
+        for frame in self.engine.history.frames() {
+            self.engine
+                .load_frame(&frame, self.force_fan, self.reads_fpga_info);
+            self.send_frame(&mut report).await?;
+            tokio::time::sleep(self.interval()).await;
+        }
+        Ok(report)
+    }
+
+    /// Return FPGA information of the devices
+    ///
+    /// The devices report FPGA information only while `reads_fpga_info` is true,
+    /// and the information is updated when the devices receive a frame.
+    pub async fn fpga_infos(&mut self) -> Result<Vec<FPGAInfo>, AUTDError> {
+        self.link
+            .lock()
+            .await
+            .receive(&mut self.engine.rx_buf)
+            .await?;
+        Ok(self
+            .engine
+            .rx_buf
+            .messages()
+            .iter()
+            .map(FPGAInfo::from)
+            .collect())
+    }
+
+    /// Enable or disable the watchdog
+    ///
+    /// If no frame is sent within `window`, a task spawned on the current tokio runtime sends `SilencerConfig::default()` and `Null` to stop outputting.
+    /// See [Controller::set_watchdog](crate::Controller::set_watchdog) for details.
+    ///
+    /// # Arguments
+    ///
+    /// * `window` - Time allowed without sending, or None to disable the watchdog
+    ///
+    pub async fn set_watchdog(&mut self, window: Option<Duration>) -> Result<(), AUTDError> {
+        self.handle_watchdog_trip();
+        self.watchdog = None;
+        let window = match window {
+            Some(window) => window,
+            None => return Ok(()),
+        };
+
+        let mut frames = self.engine.stop_frames(SilencerConfig::default())?;
+        frames.iter_mut().for_each(|tx| {
+            autd3_core::force_fan(tx, self.force_fan);
+            autd3_core::reads_fpga_info(tx, self.reads_fpga_info);
+        });
+
+        self.watchdog = Some(Watchdog::start_async(
+            self.link.clone(),
+            self.engine.msg_id.clone(),
+            frames,
+            window,
+        ));
+        Ok(())
+    }
+
+    /// Return true if the watchdog has stopped outputting and the controller has not handled it yet
+    ///
+    /// The trip is handled by [AsyncController::handle_link_events], which is called before sending data.
+    pub fn is_watchdog_triggered(&self) -> bool {
+        self.watchdog.as_ref().is_some_and(Watchdog::is_triggered)
+    }
+
+    /// Stop outputting
+    pub async fn stop(&mut self) -> Result<SendReport, AUTDError> {
+        let res = self.send(SilencerConfig::default()).await?;
+
+        // zero drives stop the output regardless of the transducer cycles
+        let res = res.merge(self.send_guarded(Null::<T>::new(), false).await?);
+
+        Ok(res)
+    }
+
+    /// Stop outputting immediately
+    ///
+    /// The silencer is disabled in the same frame, so that the output stops without fading out.
+    /// The frames are sent immediately, without restoring devices, deduplication and synchronization.
+    pub async fn emergency_stop(&mut self) -> Result<SendReport, AUTDError> {
+        let frames = self.engine.stop_frames(SilencerConfig::none())?;
+        let mut report = self.engine.new_report();
+        for (i, frame) in frames.iter().enumerate() {
+            if i > 0 {
+                tokio::time::sleep(self.interval()).await;
+            }
+            self.engine
+                .load_frame(frame, self.force_fan, self.reads_fpga_info);
+            self.send_frame(&mut report).await?;
+        }
+        Ok(report)
+    }
+
+    /// Close controller
+    pub async fn close(&mut self) -> Result<SendReport, AUTDError> {
+        self.watchdog = None;
+        let res = self.stop().await?;
+        let res = res.merge(self.clear().await?);
+        self.link.lock().await.close().await?;
+        self.closed = true;
+        Ok(res)
+    }
+}
+
+impl<L: AsyncLink + 'static, T: Transducer + Send + Sync> AsyncController<L, T> {
+    /// Handle events reported by the link
+    ///
+    /// This is called automatically before sending data.
+    /// Call this periodically to receive link events via [AsyncController::subscribe] while not sending.
+    pub async fn handle_link_events(&mut self) -> Result<SendReport, AUTDError> {
+        self.handle_watchdog_trip();
+        let events = self.link.lock().await.poll_events();
+        if self.engine.handle_link_events(events, self.auto_restore) {
+            return self.restore().await;
+        }
+        Ok(self.engine.new_report())
+    }
+
+    /// Send frames packed in advance until all frames are sent or `proceed` returns false
+    ///
+    /// Whether to synchronize is decided once before sending any frame.
+    /// `proceed` is called with the number of sent frames, excluding those for synchronization.
+    /// Return the report and the number of sent frames.
+    async fn send_frames<F: FnMut(usize) -> bool>(
+        &mut self,
+        frames: &[TxDatagram],
+        guards_sync: bool,
+        mut proceed: F,
+    ) -> Result<(SendReport, usize), AUTDError> {
+        let mut report = self.engine.new_report();
+        if self
+            .engine
+            .needs_sync(frames, guards_sync, self.sync_policy)?
+        {
+            report = report.merge(self.synchronize().await?);
+        }
+        for (i, frame) in frames.iter().enumerate() {
+            if i > 0 {
+                tokio::time::sleep(self.interval()).await;
+            }
+            self.engine
+                .load_frame(frame, self.force_fan, self.reads_fpga_info);
+            self.send_frame(&mut report).await?;
+            if !proceed(i + 1) && i + 1 < frames.len() {
+                return Ok((report, i + 1));
+            }
+        }
+        Ok((report, frames.len()))
+    }
+
+    /// Take over the stop frames if the watchdog has sent them without going through the controller
+    fn handle_watchdog_trip(&mut self) {
+        if let Some(watchdog) = &self.watchdog {
+            if watchdog.take_triggered() {
+                self.engine.handle_watchdog_trip(watchdog.frames());
+            }
+        }
+    }
+
+    /// Send a frame in `tx_buf` according to `send_policy`, and record acknowledgements to `report`
+    async fn send_frame(&mut self, report: &mut SendReport) -> Result<(), AUTDError> {
+        let policy = self.send_policy;
+        if let Some(watchdog) = &self.watchdog {
+            watchdog.feed();
+        }
+        let timeout = self.engine.begin_frame(&policy, self.interval());
+        self.link.lock().await.send(&self.engine.tx_buf).await?;
+        if timeout.is_zero() {
+            report.add_unchecked_frame();
+            return Ok(());
+        }
+
+        let mut ack = self.engine.ack_tracker();
+        for retry in 0..=policy.retries {
+            if retry > 0 {
+                tokio::time::sleep(policy.backoff.delay(retry - 1)).await;
+                self.link.lock().await.send(&self.engine.tx_buf).await?;
+            }
+            if self.wait_msg_processed(&mut ack, timeout).await? {
+                break;
+            }
+        }
+        self.engine
+            .finish_frame(ack, policy.mode, self.reads_fpga_info, report)
+    }
+
+    async fn read_acks(&mut self) -> Result<Vec<u8>, AUTDError> {
+        self.send_frame(&mut SendReport::default()).await?;
+        Ok(self.engine.acks())
+    }
+
+    async fn wait_msg_processed(
+        &mut self,
+        ack: &mut AckTracker,
+        timeout: Duration,
+    ) -> Result<bool, AUTDError> {
+        let deadline = Instant::now() + timeout;
+        let wait = self.interval();
+        loop {
+            let received = self
+                .link
+                .lock()
+                .await
+                .receive(&mut self.engine.rx_buf)
+                .await?;
+            let rx = if received {
+                Some(&self.engine.rx_buf)
+            } else {
+                None
+            };
+            if ack.update(rx) {
+                return Ok(true);
+            }
+            if Instant::now() >= deadline {
+                return Ok(false);
+            }
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    fn interval(&self) -> Duration {
+        engine::send_interval(self.send_interval)
+    }
+}
+
+impl<L: AsyncLink + 'static, T: Transducer> Drop for AsyncController<L, T> {
+    fn drop(&mut self) {
+        if self.closed {
+            return;
+        }
+        self.watchdog = None;
+        let silencer = match self.drop_behavior {
+            DropBehavior::Close => SilencerConfig::default(),
+            DropBehavior::EmergencyStop => SilencerConfig::none(),
+            DropBehavior::Keep => return,
+        };
+        // stop frames cannot be sent without a runtime, since dropping cannot wait
+        let handle = match tokio::runtime::Handle::try_current() {
+            Ok(handle) => handle,
+            Err(_) => return,
+        };
+        let frames = match self.engine.stop_frames(silencer) {
+            Ok(frames) => frames,
+            Err(_) => return,
+        };
+        let frames = frames
+            .iter()
+            .map(|frame| {
+                self.engine
+                    .load_frame(frame, self.force_fan, self.reads_fpga_info);
+                self.engine.tx_buf.clone()
+            })
+            .collect::<Vec<_>>();
+        let link = self.link.clone();
+        let interval = engine::send_interval(self.send_interval);
+        handle.spawn(async move {
+            let mut link = link.lock().await;
+            if !link.is_open() {
+                return;
+            }
+            for (i, tx) in frames.iter().enumerate() {
+                if i > 0 {
+                    tokio::time::sleep(interval).await;
+                }
+                let _ = link.send(tx).await;
+            }
+            let _ = link.close().await;
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use autd3_core::{
+        geometry::{GeometryBuilder, NormalTransducer, Vector3},
+        modulation::Modulation,
+    };
+    use autd3_firmware_emulator::Emulator;
+    use autd3_link_debug::Debug;
+
+    use super::*;
+    use crate::{
+        gain::Focus,
+        modulation::{Sine, Static},
+        transaction::Transaction,
+    };
+
+    async fn open() -> (
+        AsyncController<Debug, NormalTransducer>,
+        Arc<Mutex<Emulator>>,
+    ) {
+        let mut geometry = GeometryBuilder::new().build();
+        geometry.add_device(Vector3::zeros(), Vector3::zeros());
+        let link = Debug::new();
+        let emulator = link.emulator();
+        let mut autd = AsyncController::open(geometry, link).await.unwrap();
+        autd.send_policy = SendPolicy::checked(Duration::from_millis(100));
+        autd.clear().await.unwrap();
+        autd.synchronize().await.unwrap();
+        (autd, emulator)
+    }
+
+    fn is_stopped(emulator: &Mutex<Emulator>, step: u16) -> bool {
+        let emulator = emulator.lock().unwrap();
+        let fpga = emulator.fpga(0);
+        fpga.silencer_step() == step && fpga.drives()[0].0.iter().all(|d| d.duty == 0)
+    }
+
+    #[tokio::test]
+    async fn watchdog_trip_stops_output_and_invalidates_state() {
+        let (mut autd, emulator) = open().await;
+        autd.send(SilencerConfig::none()).await.unwrap();
+        autd.set_watchdog(Some(Duration::from_millis(30)))
+            .await
+            .unwrap();
+        let center = autd.geometry().center();
+        autd.send((Static::new(0xFF), Focus::new(center)))
+            .await
+            .unwrap();
+        assert!(!autd.is_watchdog_triggered());
+
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        assert!(autd.is_watchdog_triggered());
+        assert!(is_stopped(&emulator, 10));
+
+        autd.handle_link_events().await.unwrap();
+        assert!(!autd.is_watchdog_triggered());
+        assert!(autd.state()[0].is_none());
+        autd.close().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn drop_sends_stop_frames() {
+        let (mut autd, emulator) = open().await;
+        autd.send(SilencerConfig::none()).await.unwrap();
+        let center = autd.geometry().center();
+        autd.send((Static::new(0xFF), Focus::new(center)))
+            .await
+            .unwrap();
+        assert!(!is_stopped(&emulator, 10));
+
+        drop(autd);
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert!(is_stopped(&emulator, 10));
+    }
+
+    #[tokio::test]
+    async fn cancel_stops_after_current_frame() {
+        let (mut autd, emulator) = open().await;
+        let token = CancellationToken::new();
+
+        let mut progress = vec![];
+        let res = autd
+            .send_with_progress(Sine::new(1), &token, |p| {
+                progress.push(p);
+                token.cancel();
+            })
+            .await;
+        assert!(matches!(res, Err(AUTDError::Cancelled(_))));
+        assert_eq!(progress.len(), 1);
+        assert_eq!(progress[0].sent, 1);
+        assert!(progress[0].total > 1);
+
+        token.reset();
+        let mut last = None;
+        autd.send_with_progress(Sine::new(1), &token, |p| last = Some(p))
+            .await
+            .unwrap();
+        let last = last.unwrap();
+        assert_eq!(last.sent, last.total);
+        let mut m = Sine::new(1);
+        m.build().unwrap();
+        assert_eq!(
+            emulator.lock().unwrap().fpga(0).modulation_cycle(),
+            m.buffer().len()
+        );
+        autd.close().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn validate_reports_unsynchronized_data() {
+        let (mut autd, _) = open().await;
+        let center = autd.geometry().center();
+        assert!(autd.validate(Focus::new(center)).is_ok());
+
+        autd.clear().await.unwrap();
+        let res = autd.validate(Focus::new(center));
+        assert!(matches!(res.errors[..], [AUTDError::NotSynchronized]));
+
+        autd.sync_policy = SyncPolicy::AutoSync;
+        assert!(autd.validate(Focus::new(center)).is_ok());
+        autd.close().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn transaction_is_sent_in_order() {
+        let (mut autd, emulator) = open().await;
+        let center = autd.geometry().center();
+        let tr = Transaction::new()
+            .header(SilencerConfig::new(20, 4096))
+            .header(Sine::new(150))
+            .body(Focus::new(center));
+        let report = autd.send(tr).await.unwrap();
+        assert!(report.is_success());
+
+        {
+            let emulator = emulator.lock().unwrap();
+            let fpga = emulator.fpga(0);
+            assert_eq!(fpga.silencer_step(), 20);
+            assert!(fpga.modulation_cycle() > 2);
+            assert!(fpga.drives()[0].0.iter().any(|d| d.duty != 0));
+        }
+        autd.close().await.unwrap();
+    }
+}
@@ -12,365 +12,1431 @@
  */
 
 use std::{
-    marker::PhantomData,
-    sync::atomic::{self, AtomicU8},
+    sync::{mpsc::Receiver, Arc, Mutex, MutexGuard, PoisonError},
+    time::{Duration, Instant},
 };
 
-use anyhow::{Ok, Result};
-use itertools::Itertools;
-
 use autd3_core::{
-    geometry::{Geometry, LegacyTransducer, NormalPhaseTransducer, NormalTransducer, Transducer},
-    interface::{DatagramBody, DatagramHeader, Empty, Filled, NullBody, NullHeader, Sendable},
-    is_msg_processed,
+    geometry::{Geometry, Transducer},
+    interface::Sendable,
     link::Link,
     silencer_config::SilencerConfig,
-    FirmwareInfo, RxDatagram, TxDatagram, MSG_BEGIN, MSG_END, NUM_TRANS_IN_UNIT,
+    timer::{HighPrecisionWaiter, NormalWaiter, Waiter},
+    FPGAInfo, FirmwareInfo, TxDatagram,
 };
 
-use crate::prelude::Null;
-
-static MSG_ID: AtomicU8 = AtomicU8::new(MSG_BEGIN);
+use crate::{
+    engine::{self, Engine},
+    error::AUTDError,
+    event::ControllerEvent,
+    monitor::{ThermalEvent, ThermalMonitor},
+    periodic::{FrameContext, PeriodicStats, Scheduler},
+    policy::{DropBehavior, SendPolicy, SyncPolicy},
+    prelude::Null,
+    progress::{CancellationToken, Progress},
+    report::{AckTracker, SendReport},
+    state::DeviceState,
+    validate::Validation,
+    watchdog::Watchdog,
+};
 
-pub struct Sender<'a, 'b, L: Link, T: Transducer, S: Sendable<T>, H, B> {
-    cnt: &'a mut Controller<L, T>,
-    buf: &'b mut S,
-    sent: bool,
-    _head: PhantomData<H>,
-    _body: PhantomData<B>,
+pub struct Controller<L: Link, T: Transducer> {
+    link: Arc<Mutex<L>>,
+    engine: Engine<T>,
+    pub send_policy: SendPolicy,
+    pub send_interval: usize,
+    pub force_fan: bool,
+    pub reads_fpga_info: bool,
+    /// Wait for deadlines of [Controller::run_periodic] by spinning instead of sleeping
+    pub high_precision_timer: bool,
+    /// Behavior when dropped without calling [Controller::close]
+    pub drop_behavior: DropBehavior,
+    pub sync_policy: SyncPolicy,
+    /// Restore the devices automatically when the link reports recovery of devices
+    pub auto_restore: bool,
+    /// Skip resending modulation, silencer config, modulation delay and synchronization which the devices already hold
+    ///
+    /// Data is regarded as held only if all devices acknowledged it, so this has no effect without ack checking in `send_policy`.
+    /// Default is false.
+    pub deduplicates: bool,
+    closed: bool,
+    watchdog: Option<Watchdog>,
 }
 
-impl<'a, 'b, L: Link, T: Transducer, S: Sendable<T>> Sender<'a, 'b, L, T, S, Empty, Empty> {
-    pub fn new(cnt: &'a mut Controller<L, T>, s: &'b mut S) -> Sender<'a, 'b, L, T, S, S::H, S::B> {
-        Sender {
-            cnt,
-            buf: s,
-            sent: false,
-            _head: PhantomData,
-            _body: PhantomData,
-        }
+impl<L: Link, T: Transducer> Controller<L, T> {
+    /// Open controller with the link
+    ///
+    /// The link can be selected at runtime with `Box<dyn DynLink>`.
+    ///
+    /// ```
+    /// use autd3::prelude::*;
+    /// use autd3_link_debug::Debug;
+    ///
+    /// # fn main() -> Result<(), AUTDError> {
+    /// let mut geometry = GeometryBuilder::new().build();
+    /// geometry.add_device(Vector3::zeros(), Vector3::zeros());
+    ///
+    /// let link: Box<dyn DynLink> = match std::env::var("AUTD_LINK").as_deref() {
+    ///     Ok("debug") | Err(_) => Box::new(Debug::new()),
+    ///     Ok(name) => panic!("unknown link: {}", name),
+    /// };
+    /// let mut autd = Controller::open(geometry, link)?;
+    /// autd.clear()?;
+    /// autd.synchronize()?;
+    ///
+    /// autd.close()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn open(geometry: Geometry<T>, link: L) -> Result<Controller<L, T>, AUTDError> {
+        let mut link = link;
+        link.open(&geometry)?;
+        Ok(Controller {
+            link: Arc::new(Mutex::new(link)),
+            engine: Engine::new(geometry),
+            send_policy: SendPolicy::default(),
+            send_interval: 1,
+            force_fan: false,
+            reads_fpga_info: false,
+            high_precision_timer: false,
+            drop_behavior: DropBehavior::default(),
+            sync_policy: SyncPolicy::default(),
+            auto_restore: true,
+            deduplicates: false,
+            closed: false,
+            watchdog: None,
+        })
     }
 }
 
-impl<'a, 'b, L: Link, T: Transducer, S: Sendable<T>> Sender<'a, 'b, L, T, S, Filled, Empty> {
-    pub fn send<B: DatagramBody<T>>(mut self, b: &'b mut B) -> Result<bool> {
-        self.buf.init()?;
-        b.init()?;
+impl<L: Link, T: Transducer> Controller<L, T> {
+    pub fn geometry(&self) -> &Geometry<T> {
+        &self.engine.geometry
+    }
 
-        autd3_core::force_fan(&mut self.cnt.tx_buf, self.cnt.force_fan);
-        autd3_core::reads_fpga_info(&mut self.cnt.tx_buf, self.cnt.reads_fpga_info);
+    /// Return mutable reference to the geometry
+    ///
+    /// Devices must not be added after opening the controller.
+    /// After changing the transducer cycles, call [Controller::synchronize] or set `sync_policy` to [SyncPolicy::AutoSync].
+    pub fn geometry_mut(&mut self) -> &mut Geometry<T> {
+        &mut self.engine.geometry
+    }
 
-        loop {
-            let msg_id = self.cnt.get_id();
-            self.buf
-                .pack(msg_id, &self.cnt.geometry, &mut self.cnt.tx_buf)?;
-            b.pack(&self.cnt.geometry, &mut self.cnt.tx_buf)?;
-            self.cnt.link.send(&self.cnt.tx_buf)?;
-            let trials = self.cnt.wait_msg_processed(self.cnt.check_trials)?;
-            if (self.cnt.check_trials != 0) && (trials == self.cnt.check_trials) {
-                self.sent = true;
-                return Ok(false);
-            }
-            if self.buf.is_finished() && b.is_finished() {
-                break;
-            }
-            std::thread::sleep(std::time::Duration::from_micros(
-                self.cnt.send_interval as u64 * autd3_core::EC_CYCLE_TIME_BASE_MICRO_SEC as u64,
-            ));
-        }
-        self.sent = true;
-        Ok(true)
+    /// Return true if the transducer cycles are the same as the last synchronized ones
+    ///
+    /// ```
+    /// use autd3::prelude::*;
+    /// use autd3_link_debug::Debug;
+    ///
+    /// # fn main() -> Result<(), AUTDError> {
+    /// let mut geometry = GeometryBuilder::new().build();
+    /// geometry.add_device(Vector3::zeros(), Vector3::zeros());
+    ///
+    /// let mut autd = Controller::open(geometry, Debug::new())?;
+    /// autd.clear()?;
+    /// let center = autd.geometry().center();
+    /// assert!(matches!(
+    ///     autd.send(Focus::new(center)),
+    ///     Err(AUTDError::NotSynchronized)
+    /// ));
+    ///
+    /// autd.synchronize()?;
+    /// assert!(autd.is_synchronized());
+    /// autd.send(Focus::new(center))?;
+    ///
+    /// autd.geometry_mut()
+    ///     .transducers_mut()
+    ///     .try_for_each(|tr| tr.set_frequency(70e3))?;
+    /// assert!(!autd.is_synchronized());
+    ///
+    /// autd.sync_policy = SyncPolicy::AutoSync;
+    /// autd.send(Focus::new(center))?;
+    /// assert!(autd.is_synchronized());
+    ///
+    /// autd.close()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn is_synchronized(&self) -> bool {
+        self.engine.is_synchronized()
     }
 
-    pub fn flush(self) -> Result<bool> {
-        let mut b = NullBody::new();
-        self.send(&mut b)
+    /// Return the last confirmed state of each device
+    ///
+    /// The state is updated only with frames acknowledged by the device,
+    /// so data frames are not reflected unless `send_policy` checks their acknowledgements.
+    /// The state is None until the device acknowledges clear.
+    ///
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// use autd3::{autd3_core::modulation::Modulation, prelude::*};
+    /// use autd3_link_debug::Debug;
+    ///
+    /// # fn main() -> Result<(), AUTDError> {
+    /// let mut geometry = GeometryBuilder::new().build();
+    /// geometry.add_device(Vector3::zeros(), Vector3::zeros());
+    ///
+    /// let mut autd = Controller::open(geometry, Debug::new())?;
+    /// autd.send_policy = SendPolicy::checked(Duration::from_millis(100));
+    /// assert!(autd.state()[0].is_none());
+    ///
+    /// autd.clear()?;
+    /// autd.synchronize()?;
+    /// autd.send(SilencerConfig::new(20, 4096))?;
+    /// let center = autd.geometry().center();
+    /// let mut m = Sine::new(150);
+    /// m.build()?;
+    /// autd.send((&mut m, Focus::new(center)))?;
+    ///
+    /// let state = autd.state()[0].as_ref().unwrap();
+    /// assert!(!state.legacy_mode);
+    /// assert_eq!(state.output, OutputMode::Normal);
+    /// assert_eq!(state.silencer_step, 20);
+    /// assert!(state.is_synchronized());
+    /// assert_eq!(state.modulation.buffer, m.buffer());
+    ///
+    /// autd.close()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn state(&self) -> &[Option<DeviceState>] {
+        self.engine.state.devices()
     }
-}
 
-impl<'a, 'b, L: Link, T: Transducer, S: Sendable<T>> Sender<'a, 'b, L, T, S, Empty, Filled> {
-    pub fn send<H: DatagramHeader>(mut self, b: &'b mut H) -> Result<bool> {
-        b.init()?;
-        self.buf.init()?;
+    /// Return a receiver of events occurred in the controller and the link
+    ///
+    /// Link events are received when sending data or calling [Controller::handle_link_events].
+    ///
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// use autd3::{autd3_core::link::LinkEvent, prelude::*};
+    /// use autd3_link_debug::Debug;
+    ///
+    /// # fn main() -> Result<(), AUTDError> {
+    /// let mut geometry = GeometryBuilder::new().build();
+    /// geometry.add_device(Vector3::zeros(), Vector3::zeros());
+    ///
+    /// let link = Debug::new();
+    /// let emulator = link.emulator();
+    /// let events = link.events();
+    /// let mut autd = Controller::open(geometry, link)?;
+    /// let rx = autd.subscribe();
+    ///
+    /// autd.send_policy = SendPolicy::checked(Duration::from_millis(100));
+    /// autd.reads_fpga_info = true;
+    /// autd.clear()?;
+    /// autd.synchronize()?;
+    ///
+    /// emulator.lock().unwrap().fpga_mut(0).assert_thermal_sensor();
+    /// events.lock().unwrap().push(LinkEvent::DeviceLost(0));
+    /// autd.send(SilencerConfig::default())?;
+    ///
+    /// let received = rx.try_iter().collect::<Vec<_>>();
+    /// assert!(received.contains(&ControllerEvent::Link(LinkEvent::DeviceLost(0))));
+    /// assert!(received.contains(&ControllerEvent::Thermal(ThermalEvent {
+    ///     device: 0,
+    ///     asserted: true
+    /// })));
+    ///
+    /// autd.close()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn subscribe(&mut self) -> Receiver<ControllerEvent> {
+        self.engine.events.subscribe()
+    }
 
-        autd3_core::force_fan(&mut self.cnt.tx_buf, self.cnt.force_fan);
-        autd3_core::reads_fpga_info(&mut self.cnt.tx_buf, self.cnt.reads_fpga_info);
+    /// Send header and body to the devices
+    ///
+    /// If either of header or body finishes earlier than the other, the remaining frames are filled with null data.
+    ///
+    /// # Arguments
+    ///
+    /// * `s` - Header, body, or tuple of header and body
+    ///
+    pub fn send<S: Sendable<T>>(&mut self, s: S) -> Result<SendReport, AUTDError> {
+        self.send_guarded(s, true)
+    }
 
-        loop {
-            let msg_id = self.cnt.get_id();
-            b.pack(msg_id, &mut self.cnt.tx_buf)?;
-            self.buf
-                .pack(msg_id, &self.cnt.geometry, &mut self.cnt.tx_buf)?;
-            self.cnt.link.send(&self.cnt.tx_buf)?;
-            let trials = self.cnt.wait_msg_processed(self.cnt.check_trials)?;
-            if (self.cnt.check_trials != 0) && (trials == self.cnt.check_trials) {
-                self.sent = true;
-                return Ok(false);
-            }
-            if self.buf.is_finished() && b.is_finished() {
-                break;
-            }
-            std::thread::sleep(std::time::Duration::from_micros(
-                self.cnt.send_interval as u64 * autd3_core::EC_CYCLE_TIME_BASE_MICRO_SEC as u64,
-            ));
-        }
-        self.sent = true;
-        Ok(true)
+    /// Send header and body, checking synchronization according to `sync_policy` if `guards_sync` is true
+    fn send_guarded<S: Sendable<T>>(
+        &mut self,
+        s: S,
+        guards_sync: bool,
+    ) -> Result<SendReport, AUTDError> {
+        let restored = self.handle_link_events()?;
+        let (frames, fingerprints) = match self.engine.pack(s, self.deduplicates)? {
+            Some(packed) => packed,
+            None => return Ok(restored),
+        };
+        let (report, _) = self.send_frames(&frames, guards_sync, |_| true)?;
+        self.engine.record_fingerprints(fingerprints, &report);
+        Ok(restored.merge(report))
     }
 
-    pub fn flush(self) -> Result<bool> {
-        let mut h = NullHeader::new();
-        self.send(&mut h)
+    /// Send Null gain to stop outputting
+    ///
+    /// Zero drives stop the output regardless of the transducer cycles, so this is exempted from the synchronization check.
+    fn send_null(&mut self) -> Result<SendReport, AUTDError> {
+        self.send_guarded(Null::<T>::new(), false)
     }
-}
 
-impl<'a, 'b, L: Link, T: Transducer, S: Sendable<T>, H, B> Drop for Sender<'a, 'b, L, T, S, H, B> {
-    fn drop(&mut self) {
-        if !self.sent {
-            if self.buf.init().is_err() {
-                return;
-            }
+    /// Pack data into scratch frames to find errors before sending, without touching the link
+    ///
+    /// In addition to [validate](crate::validate), [AUTDError::NotSynchronized] is reported
+    /// if the data requires synchronization which `sync_policy` does not perform.
+    /// The transfer time is estimated with `send_interval`, and deduplication is not considered.
+    ///
+    /// # Arguments
+    ///
+    /// * `s` - Header, body, or tuple of header and body
+    ///
+    /// ```
+    /// use autd3::prelude::*;
+    /// use autd3_link_debug::Debug;
+    ///
+    /// # fn main() -> Result<(), AUTDError> {
+    /// let mut geometry = GeometryBuilder::new().build();
+    /// geometry.add_device(Vector3::zeros(), Vector3::zeros());
+    ///
+    /// let mut autd = Controller::open(geometry, Debug::new())?;
+    /// autd.clear()?;
+    ///
+    /// let center = autd.geometry().center();
+    /// let res = autd.validate(Focus::new(center));
+    /// assert!(matches!(res.errors[..], [AUTDError::NotSynchronized]));
+    ///
+    /// autd.synchronize()?;
+    /// let res = autd.validate(Focus::new(center));
+    /// assert!(res.is_ok());
+    ///
+    /// autd.close()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn validate<S: Sendable<T>>(&self, s: S) -> Validation {
+        self.engine.validate(s, self.interval(), self.sync_policy)
+    }
 
-            autd3_core::force_fan(&mut self.cnt.tx_buf, self.cnt.force_fan);
-            autd3_core::reads_fpga_info(&mut self.cnt.tx_buf, self.cnt.reads_fpga_info);
-
-            loop {
-                let msg_id = self.cnt.get_id();
-                if self
-                    .buf
-                    .pack(msg_id, &self.cnt.geometry, &mut self.cnt.tx_buf)
-                    .is_err()
-                {
-                    return;
-                }
-                if self.cnt.link.send(&self.cnt.tx_buf).is_err() {
-                    return;
-                }
-                if (self
-                    .cnt
-                    .wait_msg_processed(self.cnt.check_trials)
-                    .unwrap_or(self.cnt.check_trials)
-                    == self.cnt.check_trials)
-                    || self.buf.is_finished()
-                {
-                    break;
-                }
-                std::thread::sleep(std::time::Duration::from_micros(
-                    self.cnt.send_interval as u64 * autd3_core::EC_CYCLE_TIME_BASE_MICRO_SEC as u64,
-                ));
-            }
+    /// Send header and body to the devices with progress report and cancellation
+    ///
+    /// The token is checked between frames. If cancelled, Null gain is sent to stop STM and output,
+    /// and [AUTDError::Cancelled] is returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `s` - Header, body, or tuple of header and body
+    /// * `token` - Cancellation token
+    /// * `progress` - Callback called after each frame of the data is sent, not counting frames for synchronization
+    ///
+    /// ```
+    /// use autd3::prelude::*;
+    /// use autd3_link_debug::Debug;
+    ///
+    /// # fn main() -> Result<(), AUTDError> {
+    /// let mut geometry = GeometryBuilder::new().legacy_mode().build();
+    /// geometry.add_device(Vector3::zeros(), Vector3::zeros());
+    ///
+    /// let mut autd = Controller::open(geometry, Debug::new())?;
+    /// autd.clear()?;
+    /// autd.synchronize()?;
+    ///
+    /// let mut stm = PointSTM::new();
+    /// (0..1000).try_for_each(|i| stm.add(Vector3::new(i as f64, 0., 150.), 0))?;
+    ///
+    /// let token = CancellationToken::new();
+    /// let res = autd.send_with_progress(&mut stm, &token, |p| {
+    ///     if p.sent * 2 >= p.total {
+    ///         token.cancel();
+    ///     }
+    /// });
+    /// assert!(matches!(res, Err(AUTDError::Cancelled(_))));
+    ///
+    /// token.reset();
+    /// let mut last = None;
+    /// autd.send_with_progress(&mut stm, &token, |p| last = Some(p))?;
+    /// let last = last.unwrap();
+    /// assert_eq!(last.sent, last.total);
+    ///
+    /// autd.close()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn send_with_progress<S: Sendable<T>, F: FnMut(Progress)>(
+        &mut self,
+        s: S,
+        token: &CancellationToken,
+        mut progress: F,
+    ) -> Result<SendReport, AUTDError> {
+        if token.is_cancelled() {
+            return Err(AUTDError::Cancelled(self.engine.new_report()));
         }
-    }
-}
 
-pub struct Controller<L: Link, T: Transducer> {
-    link: L,
-    geometry: Geometry<T>,
-    tx_buf: TxDatagram,
-    rx_buf: RxDatagram,
-    pub check_trials: usize,
-    pub send_interval: usize,
-    pub force_fan: bool,
-    pub reads_fpga_info: bool,
-}
+        let restored = self.handle_link_events()?;
+        let (frames, fingerprints) = match self.engine.pack(s, self.deduplicates)? {
+            Some(packed) => packed,
+            None => return Ok(restored),
+        };
+        let total = frames.len();
 
-impl<L: Link, T: Transducer> Controller<L, T> {
-    pub fn open(geometry: Geometry<T>, link: L) -> Result<Controller<L, T>> {
-        let mut link = link;
-        link.open(&geometry)?;
-        let num_devices = geometry.num_devices();
-        Ok(Controller {
-            link,
-            geometry,
-            tx_buf: TxDatagram::new(num_devices),
-            rx_buf: RxDatagram::new(num_devices),
-            check_trials: 0,
-            send_interval: 1,
-            force_fan: false,
-            reads_fpga_info: false,
-        })
-    }
-}
+        let (report, sent) = self.send_frames(&frames, true, |sent| {
+            progress(Progress { sent, total });
+            !token.is_cancelled()
+        })?;
+        if sent == total {
+            self.engine.record_fingerprints(fingerprints, &report);
+            return Ok(restored.merge(report));
+        }
 
-impl<L: Link, T: Transducer> Controller<L, T> {
-    pub fn geometry(&self) -> &Geometry<T> {
-        &self.geometry
+        let report = restored.merge(report);
+
+        let report = report.merge(self.send_null()?);
+        Err(AUTDError::Cancelled(report))
     }
 
-    /// Send header and body to the devices
+    /// Send data built by `f` periodically until [FrameContext::stop] is called
+    ///
+    /// If sending a frame takes longer than `period`, the missed frames are skipped.
+    /// If `high_precision_timer` is true, deadlines are waited by spinning for the last millisecond to reduce jitter.
     ///
     /// # Arguments
     ///
-    /// * `header` - Header
-    /// * `body` - Body
+    /// * `period` - Period of frames, which must not be zero
+    /// * `f` - Function to build the data of each frame
+    ///
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// use autd3::prelude::*;
+    /// use autd3_link_debug::Debug;
+    ///
+    /// # fn main() -> Result<(), AUTDError> {
+    /// let mut geometry = GeometryBuilder::new().legacy_mode().build();
+    /// geometry.add_device(Vector3::zeros(), Vector3::zeros());
+    ///
+    /// let mut autd = Controller::open(geometry, Debug::new())?;
+    /// autd.clear()?;
+    /// autd.synchronize()?;
+    ///
+    /// let center = autd.geometry().center();
+    /// let stats = autd.run_periodic(Duration::from_millis(5), |ctx| {
+    ///     if ctx.index == 9 {
+    ///         ctx.stop();
+    ///     }
+    ///     let x = 10. * (ctx.time.as_secs_f64() * 100.).sin();
+    ///     Focus::new(center + Vector3::new(x, 0., 150.))
+    /// })?;
     ///
-    pub fn send<'a, 'b, S: Sendable<T>>(
-        &'a mut self,
-        s: &'b mut S,
-    ) -> Sender<'a, 'b, L, T, S, S::H, S::B> {
-        Sender::new(self, s)
+    /// assert_eq!(stats.frames, 10);
+    /// assert_eq!(stats.period.count(), 9);
+    /// assert_eq!(stats.period_histogram.counts().iter().sum::<usize>(), 9);
+    ///
+    /// autd.close()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn run_periodic<S: Sendable<T>, F: FnMut(&mut FrameContext) -> S>(
+        &mut self,
+        period: Duration,
+        f: F,
+    ) -> Result<PeriodicStats, AUTDError> {
+        if period.is_zero() {
+            return Err(AUTDError::ZeroPeriod);
+        }
+        if self.high_precision_timer {
+            self.run_periodic_with::<HighPrecisionWaiter, _, _>(period, f)
+        } else {
+            self.run_periodic_with::<NormalWaiter, _, _>(period, f)
+        }
+    }
+
+    fn run_periodic_with<W: Waiter, S: Sendable<T>, F: FnMut(&mut FrameContext) -> S>(
+        &mut self,
+        period: Duration,
+        mut f: F,
+    ) -> Result<PeriodicStats, AUTDError> {
+        let mut scheduler = Scheduler::new(period);
+        while !scheduler.is_stopped() {
+            let s = f(scheduler.wait::<W>());
+            self.send(s)?;
+            scheduler.finish();
+        }
+        Ok(scheduler.stats())
     }
 
     /// Clear all data
-    pub fn clear(&mut self) -> Result<bool> {
-        autd3_core::clear(&mut self.tx_buf);
-        self.link.send(&self.tx_buf)?;
-        let success = self.wait_msg_processed(200)? != 200;
-        Ok(success)
-    }
-
-    pub fn synchronize(&mut self) -> Result<bool> {
-        autd3_core::force_fan(&mut self.tx_buf, self.force_fan);
-        autd3_core::reads_fpga_info(&mut self.tx_buf, self.reads_fpga_info);
-
-        let msg_id = self.get_id();
-        let cycles: Vec<[u16; NUM_TRANS_IN_UNIT]> = self
-            .geometry
-            .transducers()
-            .map(|tr| tr.cycle())
-            .chunks(NUM_TRANS_IN_UNIT)
-            .into_iter()
-            .map(|c| c.collect::<Vec<_>>())
-            .map(|v| v.try_into().unwrap())
-            .collect();
+    pub fn clear(&mut self) -> Result<SendReport, AUTDError> {
+        self.engine.load_clear();
+        let mut report = self.engine.new_report();
+        self.send_frame(&mut report)?;
+        Ok(report)
+    }
 
-        autd3_core::sync(msg_id, &cycles, &mut self.tx_buf)?;
+    /// Synchronize transducer cycles of the devices
+    ///
+    /// Nothing is sent if `deduplicates` is true and the devices are already synchronized with the current cycles.
+    /// If any device does not acknowledge, the controller is regarded as not synchronized and [AUTDError::SendFailed] is returned.
+    pub fn synchronize(&mut self) -> Result<SendReport, AUTDError> {
+        if self.deduplicates && self.is_synchronized() {
+            return Ok(self.engine.new_report());
+        }
 
-        self.link.send(&self.tx_buf)?;
-        Ok(self.wait_msg_processed(200)? != 200)
+        let cycles = self
+            .engine
+            .load_sync(self.force_fan, self.reads_fpga_info)?;
+        let mut report = self.engine.new_report();
+        self.send_frame(&mut report)?;
+        self.engine.confirm_sync(cycles, report)
+    }
+
+    /// Forget the data which the devices hold, so that the next send always sends it
+    ///
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// use autd3::prelude::*;
+    /// use autd3_link_debug::Debug;
+    ///
+    /// # fn main() -> Result<(), AUTDError> {
+    /// let mut geometry = GeometryBuilder::new().build();
+    /// geometry.add_device(Vector3::zeros(), Vector3::zeros());
+    ///
+    /// let mut autd = Controller::open(geometry, Debug::new())?;
+    /// autd.send_policy = SendPolicy::checked(Duration::from_millis(100));
+    /// autd.deduplicates = true;
+    /// autd.clear()?;
+    /// autd.synchronize()?;
+    /// assert_eq!(autd.synchronize()?.frames(), 0);
+    ///
+    /// assert!(autd.send(SilencerConfig::default())?.frames() > 0);
+    /// assert!(autd.send(Sine::new(150))?.frames() > 0);
+    /// assert_eq!(autd.send(Sine::new(150))?.frames(), 0);
+    /// assert_eq!(autd.send(SilencerConfig::default())?.frames(), 0);
+    /// assert!(autd.send(Sine::new(200))?.frames() > 0);
+    ///
+    /// autd.invalidate_cache();
+    /// assert!(autd.send(Sine::new(200))?.frames() > 0);
+    /// assert_eq!(autd.synchronize()?.frames(), 1);
+    ///
+    /// autd.close()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn invalidate_cache(&mut self) {
+        self.engine.invalidate_cache();
     }
 
     /// Return firmware information of the devices
-    pub fn firmware_infos(&mut self) -> Result<Vec<FirmwareInfo>> {
-        autd3_core::cpu_version(&mut self.tx_buf);
-        self.link.send(&self.tx_buf)?;
-        self.wait_msg_processed(200)?;
-        let cpu_versions = self
-            .rx_buf
-            .messages()
-            .iter()
-            .map(|rx| rx.ack)
-            .collect::<Vec<_>>();
+    pub fn firmware_infos(&mut self) -> Result<Vec<FirmwareInfo>, AUTDError> {
+        autd3_core::cpu_version(&mut self.engine.tx_buf);
+        let cpu_versions = self.read_acks()?;
 
-        autd3_core::fpga_version(&mut self.tx_buf);
-        self.link.send(&self.tx_buf)?;
-        self.wait_msg_processed(200)?;
-        let fpga_versions = self
-            .rx_buf
-            .messages()
-            .iter()
-            .map(|rx| rx.ack)
-            .collect::<Vec<_>>();
+        autd3_core::fpga_version(&mut self.engine.tx_buf);
+        let fpga_versions = self.read_acks()?;
+
+        autd3_core::fpga_functions(&mut self.engine.tx_buf);
+        let fpga_functions = self.read_acks()?;
 
-        autd3_core::fpga_functions(&mut self.tx_buf);
-        self.link.send(&self.tx_buf)?;
-        self.wait_msg_processed(200)?;
-        let fpga_functions = self
+        Ok(self
+            .engine
+            .firmware_infos(&cpu_versions, &fpga_versions, &fpga_functions))
+    }
+
+    /// Resend the last sent data to restore the devices
+    ///
+    /// Synchronization, silencer, modulation, modulation delay and gain or STM are sent again in this order.
+    /// This is called automatically before sending if `auto_restore` is true and the link reports recovery of devices.
+    ///
+    /// ```
+    /// use autd3::{autd3_core::link::LinkEvent, prelude::*};
+    /// use autd3_link_debug::Debug;
+    ///
+    /// # fn main() -> Result<(), AUTDError> {
+    /// let mut geometry = GeometryBuilder::new().build();
+    /// geometry.add_device(Vector3::zeros(), Vector3::zeros());
+    ///
+    /// let link = Debug::new();
+    /// let emulator = link.emulator();
+    /// let events = link.events();
+    /// let mut autd = Controller::open(geometry, link)?;
+    /// autd.clear()?;
+    /// autd.synchronize()?;
+    ///
+    /// autd.send(SilencerConfig::new(20, 4096))?;
+    /// let center = autd.geometry().center();
+    /// autd.send((Sine::new(150), Focus::new(center)))?;
+    ///
+    /// // the device is power cycled
+    /// emulator.lock().unwrap().reset(0);
+    /// assert_ne!(emulator.lock().unwrap().fpga(0).silencer_step(), 20);
+    ///
+    /// autd.restore()?;
+    /// assert_eq!(emulator.lock().unwrap().fpga(0).silencer_step(), 20);
+    ///
+    /// // the link reports recovery, and the devices are restored before the next data is sent
+    /// emulator.lock().unwrap().reset(0);
+    /// events.lock().unwrap().push(LinkEvent::DeviceRecovered(0));
+    /// autd.send(Focus::new(center))?;
+    /// assert_eq!(emulator.lock().unwrap().fpga(0).silencer_step(), 20);
+    /// assert!(emulator.lock().unwrap().fpga(0).modulation_cycle() > 2);
+    ///
+    /// autd.close()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn restore(&mut self) -> Result<SendReport, AUTDError> {
+        let mut report = self.engine.new_report();
+        for frame in self.engine.history.frames() {
+            self.engine
+                .load_frame(&frame, self.force_fan, self.reads_fpga_info);
+            self.send_frame(&mut report)?;
+            std::thread::sleep(self.interval());
+        }
+        Ok(report)
+    }
+
+    /// Return FPGA information of the devices
+    ///
+    /// The devices report FPGA information only while `reads_fpga_info` is true,
+    /// and the information is updated when the devices receive a frame.
+    pub fn fpga_infos(&mut self) -> Result<Vec<FPGAInfo>, AUTDError> {
+        lock(&self.link).receive(&mut self.engine.rx_buf)?;
+        Ok(self
+            .engine
             .rx_buf
             .messages()
             .iter()
-            .map(|rx| rx.ack)
-            .collect::<Vec<_>>();
-
-        Ok((0..self.geometry.num_devices())
-            .map(|i| FirmwareInfo::new(0, cpu_versions[i], fpga_versions[i], fpga_functions[i]))
+            .map(FPGAInfo::from)
             .collect())
     }
 }
 
+/// Lock the link shared with background threads
+pub(crate) fn lock<L>(link: &Mutex<L>) -> MutexGuard<'_, L> {
+    link.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
 impl<L: Link, T: Transducer> Controller<L, T> {
+    /// Return next message ID
+    ///
+    /// Message IDs are allocated per controller, so that multiple controllers in the same process do not interfere with each other.
+    ///
+    /// ```
+    /// use autd3::prelude::*;
+    /// use autd3_link_debug::Debug;
+    ///
+    /// # fn main() -> Result<(), AUTDError> {
+    /// let open = || -> Result<Controller<Debug, LegacyTransducer>, AUTDError> {
+    ///     let mut geometry = GeometryBuilder::new().legacy_mode().build();
+    ///     geometry.add_device(Vector3::zeros(), Vector3::zeros());
+    ///     let mut autd = Controller::open(geometry, Debug::new())?;
+    ///     Ok(autd)
+    /// };
+    ///
+    /// let mut autd1 = open()?;
+    /// let mut autd2 = open()?;
+    ///
+    /// let id1 = autd1.get_id();
+    /// let id2 = autd2.get_id();
+    /// assert_eq!(id1, id2);
+    ///
+    /// for _ in 0..300 {
+    ///     assert!(autd1.send(SilencerConfig::default())?.is_success());
+    ///     assert!(autd2.send(SilencerConfig::default())?.is_success());
+    /// }
+    ///
+    /// assert_eq!(autd1.get_id(), autd2.get_id());
+    ///
+    /// autd1.close()?;
+    /// autd2.close()?;
+    /// # Ok(())
+    /// # }
+    /// ```
     pub fn get_id(&self) -> u8 {
-        if MSG_ID
-            .compare_exchange(
-                MSG_END,
-                MSG_BEGIN,
-                atomic::Ordering::SeqCst,
-                atomic::Ordering::SeqCst,
-            )
-            .is_err()
+        self.engine.msg_id.next()
+    }
+
+    /// Send frames packed in advance until all frames are sent or `proceed` returns false
+    ///
+    /// Whether to synchronize is decided once before sending any frame.
+    /// `proceed` is called with the number of sent frames, excluding those for synchronization.
+    /// Return the report and the number of sent frames.
+    fn send_frames<F: FnMut(usize) -> bool>(
+        &mut self,
+        frames: &[TxDatagram],
+        guards_sync: bool,
+        mut proceed: F,
+    ) -> Result<(SendReport, usize), AUTDError> {
+        let mut report = self.engine.new_report();
+        if self
+            .engine
+            .needs_sync(frames, guards_sync, self.sync_policy)?
         {
-            MSG_ID.fetch_add(1, atomic::Ordering::SeqCst);
+            report = report.merge(self.synchronize()?);
+        }
+        for (i, frame) in frames.iter().enumerate() {
+            if i > 0 {
+                std::thread::sleep(self.interval());
+            }
+            self.engine
+                .load_frame(frame, self.force_fan, self.reads_fpga_info);
+            self.send_frame(&mut report)?;
+            if !proceed(i + 1) && i + 1 < frames.len() {
+                return Ok((report, i + 1));
+            }
+        }
+        Ok((report, frames.len()))
+    }
+
+    /// Send frames packed in advance after handling link events
+    pub(crate) fn send_packed(&mut self, frames: &[TxDatagram]) -> Result<SendReport, AUTDError> {
+        let restored = self.handle_link_events()?;
+        let (report, _) = self.send_frames(frames, true, |_| true)?;
+        Ok(restored.merge(report))
+    }
+
+    /// Handle events reported by the link
+    ///
+    /// This is called automatically before sending data.
+    /// Call this periodically to receive link events via [Controller::subscribe] while not sending.
+    pub fn handle_link_events(&mut self) -> Result<SendReport, AUTDError> {
+        self.handle_watchdog_trip();
+        let events = lock(&self.link).poll_events();
+        if self.engine.handle_link_events(events, self.auto_restore) {
+            return self.restore();
         }
-        MSG_ID.load(atomic::Ordering::SeqCst)
+        Ok(self.engine.new_report())
     }
 
-    fn wait_msg_processed(&mut self, max_trial: usize) -> Result<usize> {
-        let msg_id = self.tx_buf.header().msg_id;
-        let wait = self.send_interval as u64 * autd3_core::EC_CYCLE_TIME_BASE_MICRO_SEC as u64;
-        let mut i = 0;
-        for _ in 0..max_trial {
-            if self.link.receive(&mut self.rx_buf)? && is_msg_processed(msg_id, &self.rx_buf) {
+    /// Take over the stop frames if the watchdog has sent them without going through the controller
+    fn handle_watchdog_trip(&mut self) {
+        if let Some(watchdog) = &self.watchdog {
+            if watchdog.take_triggered() {
+                self.engine.handle_watchdog_trip(watchdog.frames());
+            }
+        }
+    }
+
+    /// Send a frame in `tx_buf` according to `send_policy`, and record acknowledgements to `report`
+    fn send_frame(&mut self, report: &mut SendReport) -> Result<(), AUTDError> {
+        let policy = self.send_policy;
+        if let Some(watchdog) = &self.watchdog {
+            watchdog.feed();
+        }
+        let timeout = self.engine.begin_frame(&policy, self.interval());
+        lock(&self.link).send(&self.engine.tx_buf)?;
+        if timeout.is_zero() {
+            report.add_unchecked_frame();
+            return Ok(());
+        }
+
+        let mut ack = self.engine.ack_tracker();
+        for retry in 0..=policy.retries {
+            if retry > 0 {
+                std::thread::sleep(policy.backoff.delay(retry - 1));
+                lock(&self.link).send(&self.engine.tx_buf)?;
+            }
+            if self.wait_msg_processed(&mut ack, timeout)? {
                 break;
             }
-            std::thread::sleep(std::time::Duration::from_micros(wait));
-            i += 1;
         }
-        Ok(i)
+        self.engine
+            .finish_frame(ack, policy.mode, self.reads_fpga_info, report)
     }
-}
 
-impl<L: Link> Controller<L, LegacyTransducer> {
-    /// Stop outputting
-    pub fn stop(&mut self) -> Result<bool> {
-        let mut config = SilencerConfig::default();
-        let res = self.send(&mut config).flush()?;
+    fn read_acks(&mut self) -> Result<Vec<u8>, AUTDError> {
+        self.send_frame(&mut SendReport::default())?;
+        Ok(self.engine.acks())
+    }
 
-        let mut g = Null::<LegacyTransducer>::new();
+    fn wait_msg_processed(
+        &mut self,
+        ack: &mut AckTracker,
+        timeout: Duration,
+    ) -> Result<bool, AUTDError> {
+        let deadline = Instant::now() + timeout;
+        let wait = self.interval();
+        loop {
+            let rx = if lock(&self.link).receive(&mut self.engine.rx_buf)? {
+                Some(&self.engine.rx_buf)
+            } else {
+                None
+            };
+            if ack.update(rx) {
+                return Ok(true);
+            }
+            if Instant::now() >= deadline {
+                return Ok(false);
+            }
+            std::thread::sleep(wait);
+        }
+    }
 
-        let res = res & self.send(&mut g).flush()?;
+    fn interval(&self) -> Duration {
+        engine::send_interval(self.send_interval)
+    }
+}
 
-        Ok(res)
+impl<L: Link + 'static, T: Transducer> Controller<L, T> {
+    /// Enable or disable the watchdog
+    ///
+    /// If no frame is sent within `window`, a background thread sends `SilencerConfig::default()` and `Null` to stop outputting.
+    /// The frames are packed when enabling, so `force_fan` and `reads_fpga_info` at that time are used.
+    /// After a trip, the states and the deduplication cache are forgotten, and [Controller::restore] keeps outputs stopped.
+    ///
+    /// # Arguments
+    ///
+    /// * `window` - Time allowed without sending, or None to disable the watchdog
+    ///
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// use autd3::prelude::*;
+    /// use autd3_link_debug::Debug;
+    ///
+    /// # fn main() -> Result<(), AUTDError> {
+    /// let mut geometry = GeometryBuilder::new().build();
+    /// geometry.add_device(Vector3::zeros(), Vector3::zeros());
+    ///
+    /// let link = Debug::new();
+    /// let emulator = link.emulator();
+    /// let mut autd = Controller::open(geometry, link)?;
+    /// autd.clear()?;
+    /// autd.synchronize()?;
+    /// autd.send(SilencerConfig::none())?;
+    ///
+    /// autd.set_watchdog(Some(Duration::from_millis(50)))?;
+    /// let center = autd.geometry().center();
+    /// autd.send((Static::new(0xFF), Focus::new(center)))?;
+    /// assert!(!autd.is_watchdog_triggered());
+    ///
+    /// std::thread::sleep(Duration::from_millis(200));
+    /// assert!(autd.is_watchdog_triggered());
+    /// {
+    ///     let emulator = emulator.lock().unwrap();
+    ///     let fpga = emulator.fpga(0);
+    ///     assert_eq!(fpga.silencer_step(), 10);
+    ///     assert!(fpga.drives()[0].0.iter().all(|d| d.duty == 0));
+    /// }
+    ///
+    /// autd.set_watchdog(None)?;
+    /// autd.close()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_watchdog(&mut self, window: Option<Duration>) -> Result<(), AUTDError> {
+        self.handle_watchdog_trip();
+        self.watchdog = None;
+        let window = match window {
+            Some(window) => window,
+            None => return Ok(()),
+        };
+
+        let mut frames = self.engine.stop_frames(SilencerConfig::default())?;
+        frames.iter_mut().for_each(|tx| {
+            autd3_core::force_fan(tx, self.force_fan);
+            autd3_core::reads_fpga_info(tx, self.reads_fpga_info);
+        });
+
+        self.watchdog = Some(Watchdog::start(
+            self.link.clone(),
+            self.engine.msg_id.clone(),
+            frames,
+            window,
+        ));
+        Ok(())
     }
 
-    /// Close controller
-    pub fn close(&mut self) -> Result<bool> {
-        let res = self.stop()?;
-        let res = res & self.clear()?;
-        self.link.close()?;
-        Ok(res)
+    /// Start a background thread which polls FPGA information and reports changes of the thermal sensor
+    ///
+    /// `reads_fpga_info` is turned on, and the state is polled every `interval`.
+    /// To receive the events through a channel, pass a closure sending them to the channel.
+    ///
+    /// # Arguments
+    ///
+    /// * `interval` - Polling interval
+    /// * `callback` - Callback called with the device index and the new state on every change
+    ///
+    /// ```
+    /// use std::{sync::mpsc, time::Duration};
+    ///
+    /// use autd3::prelude::*;
+    /// use autd3_link_debug::Debug;
+    ///
+    /// # fn main() -> Result<(), AUTDError> {
+    /// let mut geometry = GeometryBuilder::new().legacy_mode().build();
+    /// geometry.add_device(Vector3::zeros(), Vector3::zeros());
+    /// geometry.add_device(Vector3::zeros(), Vector3::zeros());
+    ///
+    /// let link = Debug::new();
+    /// let emulator = link.emulator();
+    /// let mut autd = Controller::open(geometry, link)?;
+    ///
+    /// let (tx, rx) = mpsc::channel();
+    /// let monitor = autd.thermal_monitor(Duration::from_millis(1), move |e| {
+    ///     let _ = tx.send(e);
+    /// });
+    ///
+    /// emulator.lock().unwrap().fpga_mut(1).assert_thermal_sensor();
+    /// autd.send(SilencerConfig::default())?;
+    /// assert!(autd.fpga_infos()?[1].is_thermal_assert());
+    ///
+    /// let e = rx.recv_timeout(Duration::from_secs(1)).unwrap();
+    /// assert_eq!(e, ThermalEvent { device: 1, asserted: true });
+    ///
+    /// monitor.stop();
+    /// autd.close()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn thermal_monitor<F: FnMut(ThermalEvent) + Send + 'static>(
+        &mut self,
+        interval: Duration,
+        callback: F,
+    ) -> ThermalMonitor {
+        self.reads_fpga_info = true;
+        ThermalMonitor::start(
+            self.link.clone(),
+            self.engine.geometry.num_devices(),
+            interval,
+            callback,
+        )
     }
 }
 
-impl<L: Link> Controller<L, NormalTransducer> {
+impl<L: Link, T: Transducer> Controller<L, T> {
     /// Stop outputting
-    pub fn stop(&mut self) -> Result<bool> {
-        let mut config = SilencerConfig::default();
-        let res = self.send(&mut config).flush()?;
+    pub fn stop(&mut self) -> Result<SendReport, AUTDError> {
+        let res = self.send(SilencerConfig::default())?;
 
-        let mut g = Null::<NormalTransducer>::new();
-
-        let res = res & self.send(&mut g).flush()?;
+        let res = res.merge(self.send_null()?);
 
         Ok(res)
     }
 
+    /// Stop outputting immediately
+    ///
+    /// The silencer is disabled in the same frame, so that the output stops without fading out.
+    /// The frames are sent immediately, without restoring devices, deduplication and synchronization.
+    /// Use this for safety interlocks.
+    ///
+    /// ```
+    /// use autd3::prelude::*;
+    /// use autd3_link_debug::Debug;
+    ///
+    /// # fn main() -> Result<(), AUTDError> {
+    /// let mut geometry = GeometryBuilder::new().build();
+    /// geometry.add_device(Vector3::zeros(), Vector3::zeros());
+    ///
+    /// let link = Debug::new();
+    /// let emulator = link.emulator();
+    /// let mut autd = Controller::open(geometry, link)?;
+    /// autd.clear()?;
+    /// autd.synchronize()?;
+    ///
+    /// let center = autd.geometry().center();
+    /// autd.send((Static::new(0xFF), Focus::new(center)))?;
+    ///
+    /// autd.emergency_stop()?;
+    /// let emulator = emulator.lock().unwrap();
+    /// let fpga = emulator.fpga(0);
+    /// assert_eq!(fpga.silencer_step(), 0xFFFF);
+    /// assert!(fpga.drives()[0].0.iter().all(|d| d.duty == 0));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn emergency_stop(&mut self) -> Result<SendReport, AUTDError> {
+        self.send_stop_frames(SilencerConfig::none())
+    }
+
+    /// Send the silencer config and Null gain in the same frames
+    ///
+    /// The frames are sent directly without handling link events, deduplication and synchronization,
+    /// since stopping must not be delayed or rejected by them.
+    fn send_stop_frames(&mut self, silencer: SilencerConfig) -> Result<SendReport, AUTDError> {
+        let frames = self.engine.stop_frames(silencer)?;
+        let mut report = self.engine.new_report();
+        for (i, frame) in frames.iter().enumerate() {
+            if i > 0 {
+                std::thread::sleep(self.interval());
+            }
+            self.engine
+                .load_frame(frame, self.force_fan, self.reads_fpga_info);
+            self.send_frame(&mut report)?;
+        }
+        Ok(report)
+    }
+
+    /// Return true if the watchdog has stopped outputting and the controller has not handled it yet
+    ///
+    /// The trip is handled by [Controller::handle_link_events], which is called before sending data.
+    pub fn is_watchdog_triggered(&self) -> bool {
+        self.watchdog.as_ref().is_some_and(Watchdog::is_triggered)
+    }
+
     /// Close controller
-    pub fn close(&mut self) -> Result<bool> {
+    pub fn close(&mut self) -> Result<SendReport, AUTDError> {
+        self.watchdog = None;
         let res = self.stop()?;
-        let res = res & self.clear()?;
-        self.link.close()?;
+        let res = res.merge(self.clear()?);
+        lock(&self.link).close()?;
+        self.closed = true;
         Ok(res)
     }
 }
 
-impl<L: Link> Controller<L, NormalPhaseTransducer> {
-    /// Stop outputting
-    pub fn stop(&mut self) -> Result<bool> {
-        let mut config = SilencerConfig::default();
-        let res = self.send(&mut config).flush()?;
+impl<L: Link, T: Transducer> Drop for Controller<L, T> {
+    fn drop(&mut self) {
+        if self.closed || !lock(&self.link).is_open() {
+            return;
+        }
+        self.watchdog = None;
+        let _ = match self.drop_behavior {
+            DropBehavior::Close => self.send_stop_frames(SilencerConfig::default()),
+            DropBehavior::EmergencyStop => self.send_stop_frames(SilencerConfig::none()),
+            DropBehavior::Keep => return,
+        };
+        let _ = lock(&self.link).close();
+    }
+}
 
-        let mut g = Null::<NormalPhaseTransducer>::new();
+#[cfg(test)]
+mod tests {
+    use autd3_core::{
+        geometry::{LegacyTransducer, Vector3},
+        link::LinkEvent,
+        CPUControlFlags,
+    };
 
-        let res = res & self.send(&mut g).flush()?;
+    use super::*;
+    use crate::{
+        engine::{ack_timeout, pack_frames},
+        gain::Focus,
+        modulation::Sine,
+        policy::FailureMode,
+        test_utils::{legacy_geometry, normal_geometry, open, TestLink},
+        validate::validate,
+    };
 
-        Ok(res)
+    #[test]
+    fn default_policy_checks_only_control_frames() {
+        let (mut autd, h) = open(legacy_geometry(1));
+        assert_eq!(autd.send_policy, SendPolicy::default());
+
+        let report = autd.synchronize().unwrap();
+        assert_eq!(report.devices()[0].trials, 1);
+
+        let report = autd.send(SilencerConfig::new(20, 4096)).unwrap();
+        assert_eq!(report.frames(), 1);
+        assert_eq!(report.devices()[0].trials, 0);
+        assert_eq!(h.num_sent(), 3);
     }
 
-    /// Close controller
-    pub fn close(&mut self) -> Result<bool> {
-        let res = self.stop()?;
-        let res = res & self.clear()?;
-        self.link.close()?;
-        Ok(res)
+    #[test]
+    fn control_frames_fail_after_control_timeout() {
+        let (mut autd, h) = open(legacy_geometry(2));
+        autd.send_policy.control_timeout = Some(Duration::from_millis(2));
+        h.probe().muted.push(1);
+
+        let report = autd.clear().unwrap();
+        assert!(!report.is_success());
+        assert_eq!(report.failed_devices().collect::<Vec<_>>(), vec![1]);
+    }
+
+    #[test]
+    fn resend_lost_frame() {
+        let (mut autd, h) = open(legacy_geometry(1));
+        autd.send_policy = SendPolicy {
+            retries: 2,
+            ..SendPolicy::checked(Duration::from_millis(5))
+        };
+        let before = h.num_sent();
+        h.probe().lost = 2;
+
+        let report = autd.send(SilencerConfig::new(20, 4096)).unwrap();
+        assert!(report.is_success());
+        assert_eq!(report.frames(), 1);
+
+        let sent = h.sent_after(before);
+        assert_eq!(sent.len(), 3);
+        assert!(sent.iter().all(|tx| tx.data() == sent[0].data()));
+    }
+
+    #[test]
+    fn fail_fast_aborts_remaining_frames() {
+        let (mut autd, h) = open(legacy_geometry(2));
+        autd.send_policy = SendPolicy {
+            retries: 1,
+            mode: FailureMode::FailFast,
+            ..SendPolicy::checked(Duration::from_millis(2))
+        };
+        let before = h.num_sent();
+        h.probe().muted.push(1);
+
+        match autd.send(Sine::new(1)) {
+            Err(AUTDError::SendFailed(report)) => {
+                assert_eq!(report.frames(), 1);
+                assert_eq!(report.failed_devices().collect::<Vec<_>>(), vec![1]);
+            }
+            r => panic!("unexpected result: {:?}", r),
+        }
+        assert_eq!(h.num_sent() - before, 2);
+    }
+
+    #[test]
+    fn best_effort_sends_all_frames() {
+        let (mut autd, h) = open(legacy_geometry(2));
+        autd.send_policy = SendPolicy {
+            retries: 1,
+            ..SendPolicy::checked(Duration::from_millis(2))
+        };
+        let before = h.num_sent();
+        h.probe().muted.push(1);
+
+        let frames = validate(Sine::new(1), autd.geometry()).frames;
+        assert!(frames > 1);
+
+        let report = autd.send(Sine::new(1)).unwrap();
+        assert_eq!(report.frames(), frames);
+        assert_eq!(report.failed_devices().collect::<Vec<_>>(), vec![1]);
+        assert!(report.devices()[0].acked);
+        assert_eq!(h.num_sent() - before, frames * 2);
+    }
+
+    fn is_stop_frame(tx: &TxDatagram, step: u16) -> bool {
+        tx.header()
+            .cpu_flag
+            .contains(CPUControlFlags::CONFIG_SILENCER)
+            && !tx.header().cpu_flag.contains(CPUControlFlags::CONFIG_SYNC)
+            && tx.header().silencer_header().step == step
+    }
+
+    #[test]
+    fn emergency_stop_bypasses_link_events_and_sync() {
+        let (mut autd, h) = open(normal_geometry(1));
+        h.push_event(LinkEvent::DeviceRecovered(0));
+        let before = h.num_sent();
+
+        let report = autd.emergency_stop().unwrap();
+        assert!(report.is_success());
+
+        let sent = h.sent_after(before);
+        assert_eq!(sent.len(), report.frames());
+        assert!(is_stop_frame(&sent[0], 0xFFFF));
+        assert!(sent[1..].iter().all(|tx| !is_sync_frame(tx)));
+        assert!(h.emulator().fpga(0).drives()[0]
+            .0
+            .iter()
+            .all(|d| d.duty == 0));
+        assert!(autd.handle_link_events().unwrap().frames() > 0);
+    }
+
+    #[test]
+    fn drop_sends_stop_frames() {
+        let (mut autd, h) = open(legacy_geometry(1));
+        autd.send(SilencerConfig::none()).unwrap();
+        let before = h.num_sent();
+
+        drop(autd);
+
+        let sent = h.sent_after(before);
+        assert_eq!(sent.len(), 1);
+        assert!(is_stop_frame(&sent[0], 10));
+        assert_eq!(h.emulator().fpga(0).silencer_step(), 10);
+    }
+
+    fn trip_watchdog(autd: &mut Controller<TestLink, LegacyTransducer>) {
+        autd.set_watchdog(Some(Duration::from_millis(10))).unwrap();
+        let deadline = Instant::now() + Duration::from_secs(1);
+        while !autd.is_watchdog_triggered() {
+            assert!(Instant::now() < deadline);
+            std::thread::sleep(Duration::from_millis(5));
+        }
+    }
+
+    #[test]
+    fn watchdog_trip_is_kept_until_handled() {
+        let (mut autd, _h) = open(legacy_geometry(1));
+        trip_watchdog(&mut autd);
+
+        std::thread::sleep(Duration::from_millis(30));
+        assert!(autd.is_watchdog_triggered());
+
+        autd.handle_link_events().unwrap();
+        assert!(!autd.is_watchdog_triggered());
+        autd.set_watchdog(None).unwrap();
+    }
+
+    #[test]
+    fn watchdog_trip_invalidates_state_and_dedup_cache() {
+        let (mut autd, h) = open(legacy_geometry(1));
+        autd.deduplicates = true;
+        autd.send_policy = SendPolicy::checked(Duration::from_millis(5));
+        autd.send(SilencerConfig::none()).unwrap();
+        assert!(autd.state()[0].is_some());
+
+        trip_watchdog(&mut autd);
+        autd.set_watchdog(None).unwrap();
+        autd.handle_link_events().unwrap();
+        assert!(autd.state()[0].is_none());
+
+        let before = h.num_sent();
+        let report = autd.send(SilencerConfig::none()).unwrap();
+        assert_eq!(report.frames(), 1);
+        assert_eq!(h.num_sent() - before, 1);
+    }
+
+    #[test]
+    fn restore_after_watchdog_trip_keeps_outputs_stopped() {
+        let (mut autd, h) = open(legacy_geometry(1));
+        autd.send(SilencerConfig::none()).unwrap();
+        trip_watchdog(&mut autd);
+        autd.set_watchdog(None).unwrap();
+        autd.handle_link_events().unwrap();
+
+        let before = h.num_sent();
+        autd.restore().unwrap();
+        let silencers = h
+            .sent_after(before)
+            .into_iter()
+            .filter(|tx| {
+                tx.header()
+                    .cpu_flag
+                    .contains(CPUControlFlags::CONFIG_SILENCER)
+            })
+            .map(|tx| tx.header().silencer_header().step)
+            .collect::<Vec<_>>();
+        assert_eq!(silencers, vec![10]);
+    }
+
+    fn is_sync_frame(tx: &TxDatagram) -> bool {
+        let flag = tx.header().cpu_flag;
+        !flag.contains(CPUControlFlags::MOD) && flag.contains(CPUControlFlags::CONFIG_SYNC)
+    }
+
+    #[test]
+    fn sync_is_decided_before_sending_frames() {
+        let (mut autd, h) = open(normal_geometry(1));
+        autd.sync_policy = SyncPolicy::AutoSync;
+        let before = h.num_sent();
+
+        let center = autd.geometry().center();
+        let report = autd.send((Sine::new(150), Focus::new(center))).unwrap();
+        assert!(autd.is_synchronized());
+
+        let sent = h.sent_after(before);
+        assert_eq!(sent.len(), report.frames());
+        assert!(is_sync_frame(&sent[0]));
+        assert!(sent[1..].iter().all(|tx| !is_sync_frame(tx)));
+    }
+
+    #[test]
+    fn unsynchronized_send_is_rejected_before_sending() {
+        let (mut autd, h) = open(normal_geometry(1));
+        let before = h.num_sent();
+
+        let center = autd.geometry().center();
+        let res = autd.send((Sine::new(150), Focus::new(center)));
+        assert!(matches!(res, Err(AUTDError::NotSynchronized)));
+        assert_eq!(h.num_sent(), before);
+    }
+
+    #[test]
+    fn stop_is_exempted_from_sync() {
+        let (mut autd, h) = open(normal_geometry(1));
+        let before = h.num_sent();
+
+        autd.stop().unwrap();
+        let sent = h.sent_after(before);
+        assert!(!sent.is_empty());
+        assert!(sent.iter().all(|tx| !is_sync_frame(tx)));
+        assert!(!autd.is_synchronized());
+    }
+
+    #[test]
+    fn synchronize_requires_all_acks() {
+        let (mut autd, h) = open(normal_geometry(2));
+        autd.send_policy.control_timeout = Some(Duration::from_millis(2));
+        h.probe().muted.push(1);
+
+        match autd.synchronize() {
+            Err(AUTDError::SendFailed(report)) => {
+                assert_eq!(report.failed_devices().collect::<Vec<_>>(), vec![1]);
+            }
+            r => panic!("unexpected result: {:?}", r),
+        }
+        assert!(!autd.is_synchronized());
+
+        h.probe().muted.clear();
+        assert!(autd.synchronize().unwrap().is_confirmed());
+        assert!(autd.is_synchronized());
+    }
+
+    #[test]
+    fn modulation_frames_are_not_control_frames() {
+        let (mut autd, _h) = open(legacy_geometry(1));
+        autd.send_policy.control_timeout = Some(Duration::from_secs(1));
+        let mut m = Sine::new(150);
+        let frames = pack_frames(
+            &mut m,
+            &mut autd3_core::interface::NullBody::new(),
+            autd.geometry(),
+        )
+        .unwrap();
+        let last = frames.last().unwrap();
+        assert!(last.header().cpu_flag.contains(CPUControlFlags::MOD_END));
+        assert_eq!(
+            ack_timeout(&autd.send_policy, last, autd.interval()),
+            Duration::ZERO
+        );
+    }
+
+    #[test]
+    fn deduplication_is_disabled_by_default() {
+        let (mut autd, _h) = open(legacy_geometry(1));
+        autd.send_policy = SendPolicy::checked(Duration::from_millis(5));
+        assert!(!autd.deduplicates);
+
+        assert!(autd.send(Sine::new(150)).unwrap().frames() > 0);
+        assert!(autd.send(Sine::new(150)).unwrap().frames() > 0);
+    }
+
+    #[test]
+    fn only_confirmed_data_is_deduplicated() {
+        let (mut autd, h) = open(legacy_geometry(2));
+        autd.deduplicates = true;
+
+        // unchecked
+        assert!(autd.send(Sine::new(150)).unwrap().frames() > 0);
+        assert!(autd.send(Sine::new(150)).unwrap().frames() > 0);
+
+        autd.send_policy = SendPolicy::checked(Duration::from_millis(2));
+        h.probe().muted.push(1);
+        assert!(!autd.send(Sine::new(150)).unwrap().is_success());
+        assert!(autd.send(Sine::new(150)).unwrap().frames() > 0);
+
+        h.probe().muted.clear();
+        assert!(autd.send(Sine::new(150)).unwrap().is_confirmed());
+        assert_eq!(autd.send(Sine::new(150)).unwrap().frames(), 0);
+        assert!(autd.send(Sine::new(200)).unwrap().frames() > 0);
+    }
+
+    #[test]
+    fn progress_counts_only_frames_of_data() {
+        let (mut autd, _h) = open(normal_geometry(1));
+        autd.sync_policy = SyncPolicy::AutoSync;
+        let center = autd.geometry().center();
+        let total = validate((Sine::new(150), Focus::new(center)), autd.geometry()).frames;
+
+        let mut progress = vec![];
+        let report = autd
+            .send_with_progress(
+                (Sine::new(150), Focus::new(center)),
+                &CancellationToken::new(),
+                |p| progress.push(p),
+            )
+            .unwrap();
+        assert_eq!(report.frames(), total + 1);
+        assert_eq!(
+            progress,
+            (1..=total)
+                .map(|sent| Progress { sent, total })
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn cancel_stops_after_current_frame() {
+        let (mut autd, h) = open(legacy_geometry(1));
+        let token = CancellationToken::new();
+        let before = h.num_sent();
+
+        let mut progress = vec![];
+        let res = autd.send_with_progress(Sine::new(1), &token, |p| {
+            progress.push(p);
+            token.cancel();
+        });
+        assert!(matches!(res, Err(AUTDError::Cancelled(_))));
+        assert_eq!(progress.len(), 1);
+        assert_eq!(progress[0].sent, 1);
+
+        let sent = h.sent_after(before);
+        assert!(sent[0].header().cpu_flag.contains(CPUControlFlags::MOD));
+        assert!(sent[1..]
+            .iter()
+            .all(|tx| !tx.header().cpu_flag.contains(CPUControlFlags::MOD)));
+    }
+
+    #[test]
+    fn device_recovery_restores_before_sending() {
+        let (mut autd, h) = open(legacy_geometry(1));
+        autd.send(SilencerConfig::new(20, 4096)).unwrap();
+        autd.send(Sine::new(150)).unwrap();
+
+        h.emulator().reset(0);
+        h.push_event(LinkEvent::DeviceRecovered(0));
+        let before = h.num_sent();
+        let report = autd.send(SilencerConfig::new(30, 4096)).unwrap();
+
+        let steps = h
+            .sent_after(before)
+            .iter()
+            .filter(|tx| {
+                let flag = tx.header().cpu_flag;
+                !flag.contains(CPUControlFlags::MOD)
+                    && flag.contains(CPUControlFlags::CONFIG_SILENCER)
+            })
+            .map(|tx| tx.header().silencer_header().step)
+            .collect::<Vec<_>>();
+        assert_eq!(steps, vec![20, 30]);
+        assert_eq!(report.frames(), h.num_sent() - before);
+        assert_eq!(h.emulator().fpga(0).silencer_step(), 30);
+        assert!(h.emulator().fpga(0).modulation_cycle() > 2);
+    }
+
+    #[test]
+    fn device_recovery_without_auto_restore_forgets_cache() {
+        let (mut autd, h) = open(legacy_geometry(1));
+        autd.auto_restore = false;
+        autd.deduplicates = true;
+        autd.send_policy = SendPolicy::checked(Duration::from_millis(5));
+        autd.send(Sine::new(150)).unwrap();
+
+        h.emulator().reset(0);
+        h.push_event(LinkEvent::DeviceRecovered(0));
+        assert_eq!(autd.handle_link_events().unwrap().frames(), 0);
+        assert!(autd.send(Sine::new(150)).unwrap().frames() > 0);
+    }
+
+    #[test]
+    fn zero_period_is_rejected() {
+        let (mut autd, h) = open(legacy_geometry(1));
+        let before = h.num_sent();
+        let res = autd.run_periodic(Duration::ZERO, |ctx| {
+            ctx.stop();
+            Sine::new(150)
+        });
+        assert!(matches!(res, Err(AUTDError::ZeroPeriod)));
+        assert_eq!(h.num_sent(), before);
+    }
+
+    #[test]
+    fn disabled_transducers_are_driven_with_zero_duty() {
+        let (mut autd, h) = open(normal_geometry(2));
+        autd.synchronize().unwrap();
+        autd.geometry_mut().disable_transducer(3).unwrap();
+        autd.geometry_mut().devices_mut()[1].set_enabled(false);
+
+        let center = autd.geometry().center();
+        autd.send(Focus::new(center + Vector3::new(0., 0., 150.)))
+            .unwrap();
+
+        let emulator = h.emulator();
+        let duties = emulator.fpga(0).drives()[0].0;
+        assert!(duties[2].duty > 0);
+        assert_eq!(duties[3].duty, 0);
+        assert!(emulator.fpga(1).drives()[0].0.iter().all(|d| d.duty == 0));
     }
 }
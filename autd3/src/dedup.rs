@@ -0,0 +1,300 @@
+/*
+ * File: dedup.rs
+ * Project: src
+ * Created Date: 18/10/2026
+ * Author: Shun Suzuki
+ * -----
+ * Last Modified: 18/10/2026
+ * Modified By: Shun Suzuki (suzuki@hapis.k.u-tokyo.ac.jp)
+ * -----
+ * Copyright (c) 2026 Shun Suzuki. All rights reserved.
+ *
+ */
+
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+};
+
+use autd3_core::{
+    error::AUTDInternalError,
+    geometry::{Geometry, Transducer},
+    interface::{DatagramBody, DatagramHeader},
+    CPUControlFlags, TxDatagram, MSG_BEGIN, MSG_CLEAR,
+};
+
+/// Kind of data which is deduplicated
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Kind {
+    Modulation,
+    Silencer,
+    ModDelay,
+}
+
+impl Kind {
+    fn of_header(tx: &TxDatagram) -> Option<Self> {
+        let flag = tx.header().cpu_flag;
+        if flag.contains(CPUControlFlags::MOD) {
+            Some(Self::Modulation)
+        } else if flag.contains(CPUControlFlags::CONFIG_SILENCER) {
+            Some(Self::Silencer)
+        } else {
+            None
+        }
+    }
+
+    fn of_body(tx: &TxDatagram) -> Option<Self> {
+        let flag = tx.header().cpu_flag;
+        if flag.contains(CPUControlFlags::WRITE_BODY) && flag.contains(CPUControlFlags::MOD_DELAY) {
+            Some(Self::ModDelay)
+        } else {
+            None
+        }
+    }
+}
+
+/// Kind and content hash of data
+pub(crate) type Fingerprint = (Kind, u64);
+
+/// Fingerprints of header and body
+pub(crate) type Fingerprints = [Option<Fingerprint>; 2];
+
+/// Content hashes of data which the devices hold
+#[derive(Default)]
+pub(crate) struct DedupCache {
+    modulation: Option<u64>,
+    silencer: Option<u64>,
+    mod_delay: Option<u64>,
+}
+
+impl DedupCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn slot(&mut self, kind: Kind) -> &mut Option<u64> {
+        match kind {
+            Kind::Modulation => &mut self.modulation,
+            Kind::Silencer => &mut self.silencer,
+            Kind::ModDelay => &mut self.mod_delay,
+        }
+    }
+
+    /// Return true if the devices already hold the data
+    pub fn contains(&self, fingerprint: Option<Fingerprint>) -> bool {
+        fingerprint.is_some_and(|(kind, hash)| {
+            let slot = match kind {
+                Kind::Modulation => self.modulation,
+                Kind::Silencer => self.silencer,
+                Kind::ModDelay => self.mod_delay,
+            };
+            slot == Some(hash)
+        })
+    }
+
+    /// Record that the devices hold the data
+    pub fn insert(&mut self, fingerprint: Option<Fingerprint>) {
+        if let Some((kind, hash)) = fingerprint {
+            *self.slot(kind) = Some(hash);
+        }
+    }
+
+    pub fn invalidate(&mut self, kind: Kind) {
+        *self.slot(kind) = None;
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::new();
+    }
+
+    /// Forget the data overwritten by a sent frame
+    pub fn observe(&mut self, tx: &TxDatagram) {
+        if tx.header().msg_id == MSG_CLEAR {
+            self.clear();
+            return;
+        }
+        if let Some(kind) = Kind::of_header(tx) {
+            self.invalidate(kind);
+        }
+        if let Some(kind) = Kind::of_body(tx) {
+            self.invalidate(kind);
+        }
+    }
+}
+
+/// Compute fingerprint of the header by packing it into `tx`
+///
+/// Return None if the header is not a single kind of deduplicated data. The header is initialized again after packing.
+pub(crate) fn header_fingerprint<H: DatagramHeader>(
+    header: &mut H,
+    tx: &mut TxDatagram,
+) -> Result<Option<Fingerprint>, AUTDInternalError> {
+    let mut hasher = DefaultHasher::new();
+    let mut kind = None;
+    let fingerprint = loop {
+        header.pack(MSG_BEGIN, tx)?;
+        match (kind, Kind::of_header(tx)) {
+            (None, Some(k)) => kind = Some(k),
+            (Some(a), Some(b)) if a == b => {}
+            _ => break None,
+        }
+        let h = tx.header();
+        h.cpu_flag.bits().hash(&mut hasher);
+        h.size.hash(&mut hasher);
+        h.data.hash(&mut hasher);
+        if header.is_finished() {
+            break kind.map(|k| (k, hasher.finish()));
+        }
+    };
+    header.init()?;
+    Ok(fingerprint)
+}
+
+/// Compute fingerprint of the body by packing it into `tx`
+///
+/// Only the first frame is packed if the body is not deduplicated. The body is initialized again after packing.
+pub(crate) fn body_fingerprint<T: Transducer, B: DatagramBody<T>>(
+    body: &mut B,
+    geometry: &Geometry<T>,
+    tx: &mut TxDatagram,
+) -> Result<Option<Fingerprint>, AUTDInternalError> {
+    let mut hasher = DefaultHasher::new();
+    let fingerprint = loop {
+        if body.is_finished() {
+            break None;
+        }
+        body.pack(geometry, tx)?;
+        match Kind::of_body(tx) {
+            Some(Kind::ModDelay) => {}
+            _ => break None,
+        }
+        tx.body().iter().for_each(|b| b.data.hash(&mut hasher));
+        if body.is_finished() {
+            break Some((Kind::ModDelay, hasher.finish()));
+        }
+    };
+    body.init()?;
+    Ok(fingerprint)
+}
+
+/// Initialize header and body, and skip data which the devices already hold
+///
+/// Return header, body, and their fingerprints to be recorded after sending.
+pub(crate) fn prepare<T: Transducer, H: DatagramHeader, B: DatagramBody<T>>(
+    mut header: H,
+    mut body: B,
+    geometry: &Geometry<T>,
+    cache: &DedupCache,
+    deduplicates: bool,
+) -> Result<(Skippable<H>, Skippable<B>, Fingerprints), AUTDInternalError> {
+    header.init()?;
+    body.init()?;
+    let fingerprints = if deduplicates {
+        let mut tx = TxDatagram::new(geometry.num_devices());
+        [
+            header_fingerprint(&mut header, &mut tx)?,
+            body_fingerprint(&mut body, geometry, &mut tx)?,
+        ]
+    } else {
+        [None, None]
+    };
+    let header = Skippable {
+        skip: cache.contains(fingerprints[0]),
+        inner: header,
+    };
+    let body = Skippable {
+        skip: cache.contains(fingerprints[1]),
+        inner: body,
+    };
+    Ok((header, body, fingerprints))
+}
+
+/// Header or body which is replaced with null data if skipped
+pub(crate) struct Skippable<D> {
+    pub inner: D,
+    pub skip: bool,
+}
+
+/// Return true if nothing remains to be sent because of deduplication
+pub(crate) fn is_noop<T: Transducer, H: DatagramHeader, B: DatagramBody<T>>(
+    header: &Skippable<H>,
+    body: &Skippable<B>,
+) -> bool {
+    (header.skip || body.skip) && header.is_finished() && body.is_finished()
+}
+
+impl<H: DatagramHeader> DatagramHeader for Skippable<H> {
+    fn init(&mut self) -> Result<(), AUTDInternalError> {
+        self.inner.init()
+    }
+
+    fn pack(&mut self, msg_id: u8, tx: &mut TxDatagram) -> Result<(), AUTDInternalError> {
+        if self.skip {
+            autd3_core::null_header(msg_id, tx);
+            return Ok(());
+        }
+        self.inner.pack(msg_id, tx)
+    }
+
+    fn is_finished(&self) -> bool {
+        self.skip || self.inner.is_finished()
+    }
+}
+
+impl<T: Transducer, B: DatagramBody<T>> DatagramBody<T> for Skippable<B> {
+    fn init(&mut self) -> Result<(), AUTDInternalError> {
+        self.inner.init()
+    }
+
+    fn pack(
+        &mut self,
+        geometry: &Geometry<T>,
+        tx: &mut TxDatagram,
+    ) -> Result<(), AUTDInternalError> {
+        if self.skip {
+            autd3_core::null_body(tx);
+            return Ok(());
+        }
+        self.inner.pack(geometry, tx)
+    }
+
+    fn is_finished(&self) -> bool {
+        self.skip || self.inner.is_finished()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use autd3_core::{silencer_config::SilencerConfig, NUM_TRANS_IN_UNIT};
+
+    use super::*;
+
+    #[test]
+    fn observe_forgets_overwritten_data() {
+        let mut tx = TxDatagram::new(1);
+        let fp = header_fingerprint(&mut SilencerConfig::none(), &mut tx).unwrap();
+        assert!(matches!(fp, Some((Kind::Silencer, _))));
+        assert_ne!(
+            fp,
+            header_fingerprint(&mut SilencerConfig::default(), &mut tx).unwrap()
+        );
+
+        let mut cache = DedupCache::new();
+        cache.insert(fp);
+        assert!(cache.contains(fp));
+
+        autd3_core::null_header(MSG_BEGIN, &mut tx);
+        autd3_core::mod_delay(&[[0; NUM_TRANS_IN_UNIT]], &mut tx).unwrap();
+        cache.observe(&tx);
+        assert!(cache.contains(fp));
+
+        autd3_core::config_silencer(MSG_BEGIN, 4096, 10, &mut tx).unwrap();
+        cache.observe(&tx);
+        assert!(!cache.contains(fp));
+
+        cache.insert(fp);
+        autd3_core::clear(&mut tx);
+        cache.observe(&tx);
+        assert!(!cache.contains(fp));
+    }
+}
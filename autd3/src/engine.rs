@@ -0,0 +1,392 @@
+/*
+ * File: engine.rs
+ * Project: src
+ * Created Date: 18/10/2026
+ * Author: Shun Suzuki
+ * -----
+ * Last Modified: 18/10/2026
+ * Modified By: Shun Suzuki (suzuki@hapis.k.u-tokyo.ac.jp)
+ * -----
+ * Copyright (c) 2026 Shun Suzuki. All rights reserved.
+ *
+ */
+
+use std::{sync::Arc, time::Duration};
+
+use autd3_core::{
+    error::AUTDInternalError,
+    geometry::{Geometry, Transducer},
+    interface::{DatagramBody, DatagramHeader, Sendable},
+    link::LinkEvent,
+    silencer_config::SilencerConfig,
+    CPUControlFlags, FPGAControlFlags, FirmwareInfo, RxDatagram, TxDatagram, MSG_BEGIN,
+    NUM_TRANS_IN_UNIT,
+};
+
+use crate::{
+    dedup::{self, DedupCache, Fingerprints},
+    error::AUTDError,
+    event::{ControllerEvent, EventHub},
+    history::FrameHistory,
+    msg_id::MsgIdAllocator,
+    policy::{FailureMode, SendPolicy, SyncPolicy},
+    prelude::Null,
+    report::{AckTracker, SendReport},
+    state::StateMirror,
+    validate::{validate_with_interval, Validation},
+};
+
+/// Packing and acknowledgement tracking shared by [Controller](crate::Controller) and [AsyncController](crate::AsyncController)
+///
+/// The controllers only perform I/O with the link and waits between frames, and leave the rest to this.
+pub(crate) struct Engine<T: Transducer> {
+    pub geometry: Geometry<T>,
+    pub tx_buf: TxDatagram,
+    pub rx_buf: RxDatagram,
+    pub msg_id: Arc<MsgIdAllocator>,
+    pub state: StateMirror,
+    pub history: FrameHistory,
+    pub dedup_cache: DedupCache,
+    pub synced_cycles: Option<Vec<[u16; NUM_TRANS_IN_UNIT]>>,
+    pub events: EventHub,
+}
+
+impl<T: Transducer> Engine<T> {
+    pub fn new(geometry: Geometry<T>) -> Self {
+        let num_devices = geometry.num_devices();
+        Self {
+            geometry,
+            tx_buf: TxDatagram::new(num_devices),
+            rx_buf: RxDatagram::new(num_devices),
+            msg_id: Arc::new(MsgIdAllocator::new()),
+            state: StateMirror::new(num_devices),
+            history: FrameHistory::new(),
+            dedup_cache: DedupCache::new(),
+            synced_cycles: None,
+            events: EventHub::new(num_devices),
+        }
+    }
+
+    pub fn new_report(&self) -> SendReport {
+        SendReport::new(self.geometry.num_devices())
+    }
+
+    /// Return true if the transducer cycles are the same as the last synchronized ones
+    pub fn is_synchronized(&self) -> bool {
+        self.synced_cycles
+            .as_ref()
+            .is_some_and(|c| *c == geometry_cycles(&self.geometry))
+    }
+
+    /// Pack all frames of the data, skipping parts which the devices already hold if `deduplicates` is true
+    ///
+    /// Return None if nothing has to be sent.
+    pub fn pack<S: Sendable<T>>(
+        &self,
+        s: S,
+        deduplicates: bool,
+    ) -> Result<Option<(Vec<TxDatagram>, Fingerprints)>, AUTDError> {
+        let (header, body) = s.operation();
+        let (mut header, mut body, fingerprints) = dedup::prepare(
+            header,
+            body,
+            &self.geometry,
+            &self.dedup_cache,
+            deduplicates,
+        )?;
+        if dedup::is_noop(&header, &body) {
+            return Ok(None);
+        }
+        let frames = collect_frames(&mut header, &mut body, &self.geometry)?;
+        Ok(Some((frames, fingerprints)))
+    }
+
+    /// Pack the silencer config and Null gain in the same frames
+    pub fn stop_frames(&self, mut silencer: SilencerConfig) -> Result<Vec<TxDatagram>, AUTDError> {
+        Ok(pack_frames(
+            &mut silencer,
+            &mut Null::<T>::new(),
+            &self.geometry,
+        )?)
+    }
+
+    /// Return true if synchronization must be sent before the frames
+    ///
+    /// Whether to synchronize is decided once for all frames of the data.
+    /// [AUTDError::NotSynchronized] is returned if synchronization is required but `sync_policy` does not perform it.
+    pub fn needs_sync(
+        &self,
+        frames: &[TxDatagram],
+        guards_sync: bool,
+        sync_policy: SyncPolicy,
+    ) -> Result<bool, AUTDError> {
+        if !guards_sync || self.is_synchronized() || !frames.iter().any(requires_sync) {
+            return Ok(false);
+        }
+        if sync_policy == SyncPolicy::Error {
+            return Err(AUTDError::NotSynchronized);
+        }
+        Ok(true)
+    }
+
+    /// Pack data into scratch frames to find errors, considering synchronization required by `sync_policy`
+    pub fn validate<S: Sendable<T>>(
+        &self,
+        s: S,
+        interval: Duration,
+        sync_policy: SyncPolicy,
+    ) -> Validation {
+        let mut res = validate_with_interval(s, &self.geometry, interval);
+        if res.requires_sync && !self.is_synchronized() && sync_policy == SyncPolicy::Error {
+            res.errors.push(AUTDError::NotSynchronized);
+        }
+        res
+    }
+
+    /// Load a frame packed in advance into `tx_buf` with a new message ID and the current flags
+    pub fn load_frame(&mut self, frame: &TxDatagram, force_fan: bool, reads_fpga_info: bool) {
+        self.tx_buf.clone_from(frame);
+        self.tx_buf.header_mut().msg_id = self.msg_id.next();
+        autd3_core::force_fan(&mut self.tx_buf, force_fan);
+        autd3_core::reads_fpga_info(&mut self.tx_buf, reads_fpga_info);
+    }
+
+    /// Pack clear into `tx_buf`, after which the devices are not synchronized
+    pub fn load_clear(&mut self) {
+        self.synced_cycles = None;
+        autd3_core::clear(&mut self.tx_buf);
+    }
+
+    /// Pack synchronize into `tx_buf` and return the cycles to be confirmed by [Engine::confirm_sync]
+    ///
+    /// The controller is regarded as not synchronized until the cycles are confirmed.
+    pub fn load_sync(
+        &mut self,
+        force_fan: bool,
+        reads_fpga_info: bool,
+    ) -> Result<Vec<[u16; NUM_TRANS_IN_UNIT]>, AUTDError> {
+        autd3_core::force_fan(&mut self.tx_buf, force_fan);
+        autd3_core::reads_fpga_info(&mut self.tx_buf, reads_fpga_info);
+
+        let msg_id = self.msg_id.next();
+        let cycles = geometry_cycles(&self.geometry);
+        autd3_core::sync(msg_id, &cycles, &mut self.tx_buf)?;
+        self.synced_cycles = None;
+        Ok(cycles)
+    }
+
+    /// Regard the devices as synchronized with `cycles` only if all devices acknowledged
+    pub fn confirm_sync(
+        &mut self,
+        cycles: Vec<[u16; NUM_TRANS_IN_UNIT]>,
+        report: SendReport,
+    ) -> Result<SendReport, AUTDError> {
+        if !report.is_confirmed() {
+            return Err(AUTDError::SendFailed(report));
+        }
+        self.synced_cycles = Some(cycles);
+        Ok(report)
+    }
+
+    /// Record the frame in `tx_buf` as sent, and return the timeout to wait for acknowledgements
+    ///
+    /// The timeout is zero if acknowledgements are not checked.
+    pub fn begin_frame(&mut self, policy: &SendPolicy, interval: Duration) -> Duration {
+        self.history.record(&self.tx_buf);
+        self.dedup_cache.observe(&self.tx_buf);
+        ack_timeout(policy, &self.tx_buf, interval)
+    }
+
+    pub fn ack_tracker(&self) -> AckTracker {
+        AckTracker::new(self.tx_buf.header().msg_id, self.geometry.num_devices())
+    }
+
+    /// Record acknowledgements of the frame in `tx_buf` to the state mirror, the events and `report`
+    ///
+    /// [AUTDError::SendFailed] is returned if any device did not acknowledge and `mode` is [FailureMode::FailFast].
+    pub fn finish_frame(
+        &mut self,
+        ack: AckTracker,
+        mode: FailureMode,
+        reads_fpga_info: bool,
+        report: &mut SendReport,
+    ) -> Result<(), AUTDError> {
+        let msg_id = self.tx_buf.header().msg_id;
+        self.state.update(&self.tx_buf, ack.acked_devices());
+        ack.acked_devices()
+            .enumerate()
+            .filter(|(_, acked)| !acked)
+            .for_each(|(device, _)| {
+                self.events
+                    .emit(ControllerEvent::AckTimeout { device, msg_id })
+            });
+        if reads_fpga_info {
+            self.events
+                .observe_fpga_info(&self.rx_buf, ack.acked_devices());
+        }
+
+        let acked = ack.is_acked();
+        report.add_frame(ack);
+        if !acked && mode == FailureMode::FailFast {
+            return Err(AUTDError::SendFailed(report.clone()));
+        }
+        Ok(())
+    }
+
+    /// Record the data as held by the devices if all devices acknowledged all frames of it
+    pub fn record_fingerprints(&mut self, fingerprints: Fingerprints, report: &SendReport) {
+        if report.is_confirmed() {
+            fingerprints
+                .into_iter()
+                .for_each(|fp| self.dedup_cache.insert(fp));
+        }
+    }
+
+    /// Emit events reported by the link, and return true if the devices have to be restored
+    ///
+    /// If devices recovered and `auto_restore` is false, the cache is forgotten instead.
+    pub fn handle_link_events(&mut self, events: Vec<LinkEvent>, auto_restore: bool) -> bool {
+        let recovered = events
+            .iter()
+            .any(|e| matches!(e, LinkEvent::DeviceRecovered(_)));
+        events
+            .into_iter()
+            .for_each(|e| self.events.emit(ControllerEvent::Link(e)));
+        if recovered && !auto_restore {
+            self.invalidate_cache();
+        }
+        recovered && auto_restore
+    }
+
+    /// Take over the stop frames sent by the watchdog without going through the controller
+    ///
+    /// The frames are recorded so that restoring does not resume outputting,
+    /// and the state mirror and the dedup cache are forgotten since the frames were not acknowledged.
+    pub fn handle_watchdog_trip(&mut self, frames: &[TxDatagram]) {
+        frames.iter().for_each(|tx| self.history.record(tx));
+        self.state.invalidate();
+        self.dedup_cache.clear();
+    }
+
+    pub fn invalidate_cache(&mut self) {
+        self.dedup_cache.clear();
+        self.synced_cycles = None;
+    }
+
+    /// Return acknowledgements of the devices in `rx_buf`
+    pub fn acks(&self) -> Vec<u8> {
+        self.rx_buf.messages().iter().map(|rx| rx.ack).collect()
+    }
+
+    /// Build firmware information from the acknowledgements of version requests, and report mismatches
+    pub fn firmware_infos(
+        &mut self,
+        cpu_versions: &[u8],
+        fpga_versions: &[u8],
+        fpga_functions: &[u8],
+    ) -> Vec<FirmwareInfo> {
+        let infos = (0..self.geometry.num_devices())
+            .map(|i| FirmwareInfo::new(i, cpu_versions[i], fpga_versions[i], fpga_functions[i]))
+            .collect::<Vec<_>>();
+        infos
+            .iter()
+            .filter(|info| !info.is_version_matched())
+            .for_each(|info| {
+                self.events
+                    .emit(ControllerEvent::FirmwareMismatch(info.clone()))
+            });
+        infos
+    }
+}
+
+/// Pack a frame of header and body
+///
+/// Null data is packed instead of the finished one.
+pub(crate) fn pack_frame<T: Transducer, H: DatagramHeader, B: DatagramBody<T>>(
+    msg_id: u8,
+    header: &mut H,
+    body: &mut B,
+    geometry: &Geometry<T>,
+    tx: &mut TxDatagram,
+) -> Result<(), AUTDInternalError> {
+    if header.is_finished() {
+        autd3_core::null_header(msg_id, tx);
+    } else {
+        header.pack(msg_id, tx)?;
+    }
+    if body.is_finished() {
+        autd3_core::null_body(tx);
+    } else {
+        body.pack(geometry, tx)?;
+    }
+    Ok(())
+}
+
+/// Pack all frames of header and body in advance
+///
+/// Message IDs of the frames must be overwritten on sending.
+pub(crate) fn pack_frames<T: Transducer, H: DatagramHeader, B: DatagramBody<T>>(
+    header: &mut H,
+    body: &mut B,
+    geometry: &Geometry<T>,
+) -> Result<Vec<TxDatagram>, AUTDInternalError> {
+    header.init()?;
+    body.init()?;
+    collect_frames(header, body, geometry)
+}
+
+/// Pack all remaining frames of initialized header and body
+///
+/// Message IDs of the frames must be overwritten on sending.
+pub(crate) fn collect_frames<T: Transducer, H: DatagramHeader, B: DatagramBody<T>>(
+    header: &mut H,
+    body: &mut B,
+    geometry: &Geometry<T>,
+) -> Result<Vec<TxDatagram>, AUTDInternalError> {
+    let mut tx = TxDatagram::new(geometry.num_devices());
+    let mut frames = vec![];
+    loop {
+        pack_frame(MSG_BEGIN, header, body, geometry, &mut tx)?;
+        frames.push(tx.clone());
+        if header.is_finished() && body.is_finished() {
+            return Ok(frames);
+        }
+    }
+}
+
+/// Return true if the frame contains drive data which depends on the transducer cycles
+///
+/// Stopping with Null gain is exempted by the caller, not by the contents of the frame.
+pub(crate) fn requires_sync(tx: &TxDatagram) -> bool {
+    let header = tx.header();
+    !header.fpga_flag.contains(FPGAControlFlags::LEGACY_MODE)
+        && header.cpu_flag.contains(CPUControlFlags::WRITE_BODY)
+        && !header.cpu_flag.contains(CPUControlFlags::MOD_DELAY)
+}
+
+/// Return the timeout to wait for acknowledgements of the frame, or zero if they are not checked
+///
+/// Clear, synchronize and firmware information requests are control frames, whose acknowledgements are always checked.
+pub(crate) fn ack_timeout(policy: &SendPolicy, tx: &TxDatagram, interval: Duration) -> Duration {
+    let header = tx.header();
+    // CONFIG_SYNC shares the bit with MOD_END
+    let is_sync = !header.cpu_flag.contains(CPUControlFlags::MOD)
+        && header.cpu_flag.contains(CPUControlFlags::CONFIG_SYNC);
+    if header.msg_id < MSG_BEGIN || is_sync {
+        policy.control_frame_timeout(interval)
+    } else {
+        policy.timeout
+    }
+}
+
+/// Return transducer cycles of the geometry in the layout of synchronize
+pub(crate) fn geometry_cycles<T: Transducer>(
+    geometry: &Geometry<T>,
+) -> Vec<[u16; NUM_TRANS_IN_UNIT]> {
+    geometry.device_slots(|tr| tr.cycle(), 4096)
+}
+
+/// Interval between frames
+pub(crate) fn send_interval(send_interval: usize) -> Duration {
+    Duration::from_micros(send_interval as u64 * autd3_core::EC_CYCLE_TIME_BASE_MICRO_SEC as u64)
+}
@@ -11,10 +11,85 @@
  *
  */
 
+use autd3_core::{error::AUTDInternalError, CPUError, DriverError, FPGAError};
 use thiserror::Error;
 
+use crate::report::SendReport;
+
+/// Error of autd3
+///
+/// Errors from autd3-core are flattened, so that each layer can be matched directly.
+/// Errors of links and gains are defined in their crates, such as `autd3_link_soem::SOEMError`,
+/// `autd3_link_twincat::AdsError` and `autd3_gain_holo::HoloError`, and can be obtained with [AUTDError::downcast_ref].
 #[derive(Error, Debug)]
 pub enum AUTDError {
+    #[error(transparent)]
+    CPU(#[from] CPUError),
+    #[error(transparent)]
+    FPGA(#[from] FPGAError),
+    #[error("Link is closed.")]
+    LinkClosed,
+    /// Error specific to each link, such as SOEM or TwinCAT. Use [AUTDError::downcast_ref] to get the typed error.
+    #[error(transparent)]
+    Link(Box<dyn std::error::Error + Send + Sync>),
+    /// Error in calculating gain, such as holo solvers. Use [AUTDError::downcast_ref] to get the typed error.
+    #[error(transparent)]
+    Gain(Box<dyn std::error::Error + Send + Sync>),
     #[error("Device id ({0}) is specified, but only {1} AUTDs are connected.")]
     GroupedOutOfRange(usize, usize),
+    #[error("Devices did not acknowledge: {0}")]
+    SendFailed(SendReport),
+    #[error("Transducer cycles are not synchronized. Call synchronize before sending data in normal mode")]
+    NotSynchronized,
+    #[error("Sending was cancelled: {0}")]
+    Cancelled(SendReport),
+    #[error("Period must not be zero")]
+    ZeroPeriod,
+    #[error(transparent)]
+    Internal(AUTDInternalError),
+}
+
+impl AUTDError {
+    /// Return the error of the link or the gain if it is `E`
+    ///
+    /// ```
+    /// use autd3::prelude::*;
+    ///
+    /// #[derive(Debug, thiserror::Error)]
+    /// #[error("No AUTD device was found")]
+    /// struct NoDeviceFound;
+    ///
+    /// let e = AUTDError::Link(Box::new(NoDeviceFound));
+    /// assert!(e.downcast_ref::<NoDeviceFound>().is_some());
+    /// assert!(AUTDError::LinkClosed.downcast_ref::<NoDeviceFound>().is_none());
+    /// ```
+    pub fn downcast_ref<E: std::error::Error + 'static>(&self) -> Option<&E> {
+        match self {
+            Self::Link(e) | Self::Gain(e) => e.downcast_ref(),
+            _ => None,
+        }
+    }
+}
+
+impl From<AUTDInternalError> for AUTDError {
+    fn from(e: AUTDInternalError) -> Self {
+        match e {
+            AUTDInternalError::CPU(e) => Self::CPU(e),
+            AUTDInternalError::FPGA(e) => Self::FPGA(e),
+            AUTDInternalError::LinkClosed => Self::LinkClosed,
+            AUTDInternalError::Link(e) => Self::Link(e),
+            AUTDInternalError::Gain(e) => Self::Gain(e),
+            AUTDInternalError::GroupedOutOfRange(a, b) => Self::GroupedOutOfRange(a, b),
+            e => Self::Internal(e),
+        }
+    }
+}
+
+impl From<DriverError> for AUTDError {
+    fn from(e: DriverError) -> Self {
+        match e {
+            DriverError::CPU(e) => Self::CPU(e),
+            DriverError::FPGA(e) => Self::FPGA(e),
+        }
+    }
 }
@@ -0,0 +1,65 @@
+/*
+ * File: event.rs
+ * Project: src
+ * Created Date: 18/10/2026
+ * Author: Shun Suzuki
+ * -----
+ * Last Modified: 18/10/2026
+ * Modified By: Shun Suzuki (suzuki@hapis.k.u-tokyo.ac.jp)
+ * -----
+ * Copyright (c) 2026 Shun Suzuki. All rights reserved.
+ *
+ */
+
+use std::sync::mpsc::{channel, Receiver, Sender};
+
+use autd3_core::{link::LinkEvent, FirmwareInfo, RxDatagram};
+
+use crate::monitor::{ThermalDetector, ThermalEvent};
+
+/// Event occurred in the controller
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ControllerEvent {
+    /// Event reported by the link, such as lost and recovered devices
+    Link(LinkEvent),
+    /// The device did not acknowledge the frame within the timeout of `send_policy`
+    AckTimeout { device: usize, msg_id: u8 },
+    /// Thermal sensor state of the device changed. Detected only while `reads_fpga_info` is true.
+    Thermal(ThermalEvent),
+    /// CPU and FPGA firmware versions of the device do not match
+    FirmwareMismatch(FirmwareInfo),
+}
+
+/// Subscribers of controller events
+pub(crate) struct EventHub {
+    subscribers: Vec<Sender<ControllerEvent>>,
+    thermal: ThermalDetector,
+}
+
+impl EventHub {
+    pub fn new(num_devices: usize) -> Self {
+        Self {
+            subscribers: vec![],
+            thermal: ThermalDetector::new(num_devices),
+        }
+    }
+
+    pub fn subscribe(&mut self) -> Receiver<ControllerEvent> {
+        let (tx, rx) = channel();
+        self.subscribers.push(tx);
+        rx
+    }
+
+    /// Send the event to all subscribers, and forget the ones whose receiver is dropped
+    pub fn emit(&mut self, event: ControllerEvent) {
+        self.subscribers.retain(|tx| tx.send(event.clone()).is_ok());
+    }
+
+    /// Emit thermal events of the devices whose sensor state changed
+    pub fn observe_fpga_info(&mut self, rx: &RxDatagram, acked: impl Iterator<Item = bool>) {
+        self.thermal
+            .detect(rx, acked)
+            .into_iter()
+            .for_each(|e| self.emit(ControllerEvent::Thermal(e)));
+    }
+}
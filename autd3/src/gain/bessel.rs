@@ -12,6 +12,7 @@
  */
 
 use autd3_core::{
+    error::AUTDInternalError,
     gain::{Gain, GainProps, IGain},
     geometry::{Geometry, Transducer, UnitQuaternion, Vector3},
 };
@@ -60,7 +61,7 @@ impl<T: Transducer> Bessel<T> {
 }
 
 impl<T: Transducer> IGain<T> for Bessel<T> {
-    fn calc(&mut self, geometry: &Geometry<T>) -> anyhow::Result<()> {
+    fn calc(&mut self, geometry: &Geometry<T>) -> Result<(), AUTDInternalError> {
         let dir = self.dir.normalize();
         let v = Vector3::new(dir.y, -dir.x, 0.);
         let theta_v = v.norm().asin();
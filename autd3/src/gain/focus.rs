@@ -12,6 +12,7 @@
  */
 
 use autd3_core::{
+    error::AUTDInternalError,
     gain::{Gain, GainProps, IGain},
     geometry::{Geometry, Transducer, Vector3},
 };
@@ -54,7 +55,7 @@ impl<T: Transducer> Focus<T> {
 }
 
 impl<T: Transducer> IGain<T> for Focus<T> {
-    fn calc(&mut self, geometry: &Geometry<T>) -> anyhow::Result<()> {
+    fn calc(&mut self, geometry: &Geometry<T>) -> Result<(), AUTDInternalError> {
         geometry.transducers().for_each(|tr| {
             let dist = (self.pos - tr.position()).norm();
             let phase = tr.align_phase_at(dist, geometry.sound_speed());
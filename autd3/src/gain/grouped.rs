@@ -14,14 +14,13 @@
 use std::collections::HashMap;
 
 use autd3_core::{
+    error::AUTDInternalError,
     gain::{Gain, GainProps, IGain},
     geometry::{Geometry, Transducer},
 };
 
 use autd3_traits::Gain;
 
-use crate::error::AUTDError;
-
 /// Gain to produce single focal point
 #[derive(Gain)]
 pub struct Grouped<'a, T: Transducer> {
@@ -47,19 +46,23 @@ impl<'a, T: Transducer> IGain<T> for Grouped<'a, T>
 where
     Grouped<'a, T>: Gain<T>,
 {
-    fn calc(&mut self, geometry: &Geometry<T>) -> anyhow::Result<()> {
+    fn calc(&mut self, geometry: &Geometry<T>) -> Result<(), AUTDInternalError> {
         for gain in self.gain_map.values_mut() {
             gain.build(geometry)?;
         }
 
         self.gain_map.iter().try_for_each(|(dev_id, gain)| {
             if *dev_id >= geometry.num_devices() {
-                return Err(AUTDError::GroupedOutOfRange(*dev_id, geometry.num_devices()).into());
+                return Err(AUTDInternalError::GroupedOutOfRange(
+                    *dev_id,
+                    geometry.num_devices(),
+                ));
             }
 
-            self.props.drives[*dev_id..(*dev_id + autd3_core::NUM_TRANS_IN_UNIT)].copy_from_slice(
-                &gain.drives()[*dev_id..(*dev_id + autd3_core::NUM_TRANS_IN_UNIT)],
-            );
+            geometry.devices()[*dev_id]
+                .transducers()
+                .iter()
+                .for_each(|tr| self.props.drives[tr.id()] = gain.drives()[tr.id()]);
 
             Ok(())
         })
@@ -12,6 +12,7 @@
  */
 
 use autd3_core::{
+    error::AUTDInternalError,
     gain::{Gain, GainProps, IGain},
     geometry::{Geometry, Transducer},
 };
@@ -34,7 +35,7 @@ impl<T: Transducer> Null<T> {
 }
 
 impl<T: Transducer> IGain<T> for Null<T> {
-    fn calc(&mut self, geometry: &Geometry<T>) -> anyhow::Result<()> {
+    fn calc(&mut self, geometry: &Geometry<T>) -> Result<(), AUTDInternalError> {
         geometry.transducers().for_each(|tr| {
             self.props.drives[tr.id()].amp = 0.0;
             self.props.drives[tr.id()].phase = 0.0;
@@ -12,6 +12,7 @@
  */
 
 use autd3_core::{
+    error::AUTDInternalError,
     gain::{Gain, GainProps, IGain},
     geometry::{Geometry, Transducer, Vector3},
 };
@@ -54,7 +55,7 @@ impl<T: Transducer> Plane<T> {
 }
 
 impl<T: Transducer> IGain<T> for Plane<T> {
-    fn calc(&mut self, geometry: &Geometry<T>) -> anyhow::Result<()> {
+    fn calc(&mut self, geometry: &Geometry<T>) -> Result<(), AUTDInternalError> {
         geometry.transducers().for_each(|tr| {
             let dist = self.dir.dot(tr.position());
             let phase = tr.align_phase_at(dist, geometry.sound_speed());
@@ -14,6 +14,7 @@
 use std::collections::HashMap;
 
 use autd3_core::{
+    error::AUTDInternalError,
     gain::{Gain, GainProps, IGain},
     geometry::{Geometry, Transducer},
 };
@@ -42,7 +43,7 @@ impl<T: Transducer> TransducerTest<T> {
 }
 
 impl<T: Transducer> IGain<T> for TransducerTest<T> {
-    fn calc(&mut self, geometry: &Geometry<T>) -> anyhow::Result<()> {
+    fn calc(&mut self, geometry: &Geometry<T>) -> Result<(), AUTDInternalError> {
         geometry.transducers().for_each(|tr| {
             if let Some((phase, amp)) = self.test_drive.get(&tr.id()) {
                 self.props.drives[tr.id()].amp = *amp;
@@ -0,0 +1,113 @@
+/*
+ * File: history.rs
+ * Project: src
+ * Created Date: 18/10/2026
+ * Author: Shun Suzuki
+ * -----
+ * Last Modified: 18/10/2026
+ * Modified By: Shun Suzuki (suzuki@hapis.k.u-tokyo.ac.jp)
+ * -----
+ * Copyright (c) 2026 Shun Suzuki. All rights reserved.
+ *
+ */
+
+use autd3_core::{CPUControlFlags, FPGAControlFlags, TxDatagram, MSG_BEGIN, MSG_CLEAR, MSG_END};
+
+/// Last frames which determine the data on the devices, used to restore cleared devices
+#[derive(Default)]
+pub(crate) struct FrameHistory {
+    sync: Option<TxDatagram>,
+    silencer: Option<TxDatagram>,
+    modulation: Vec<TxDatagram>,
+    pending_mod: Vec<TxDatagram>,
+    mod_delay: Option<TxDatagram>,
+    drives: Vec<TxDatagram>,
+    pending_stm: Vec<TxDatagram>,
+}
+
+impl FrameHistory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a sent frame
+    pub fn record(&mut self, tx: &TxDatagram) {
+        let header = tx.header();
+        if header.msg_id == MSG_CLEAR {
+            *self = Self::new();
+            return;
+        }
+        if !(MSG_BEGIN..=MSG_END).contains(&header.msg_id) {
+            return;
+        }
+
+        let cpu_flag = header.cpu_flag;
+        if cpu_flag.contains(CPUControlFlags::MOD) {
+            if cpu_flag.contains(CPUControlFlags::MOD_BEGIN) {
+                self.pending_mod.clear();
+            }
+            self.pending_mod.push(Self::header_part(tx));
+            if cpu_flag.contains(CPUControlFlags::MOD_END) {
+                self.modulation = std::mem::take(&mut self.pending_mod);
+            }
+        } else if cpu_flag.contains(CPUControlFlags::CONFIG_SILENCER) {
+            self.silencer = Some(Self::header_part(tx));
+        } else if cpu_flag.contains(CPUControlFlags::CONFIG_SYNC) {
+            self.sync = Some(tx.clone());
+            return;
+        }
+
+        if !cpu_flag.contains(CPUControlFlags::WRITE_BODY) {
+            return;
+        }
+        let body = Self::body_part(tx);
+        let fpga_flag = header.fpga_flag;
+        if cpu_flag.contains(CPUControlFlags::MOD_DELAY) {
+            self.mod_delay = Some(body);
+        } else if fpga_flag.contains(FPGAControlFlags::STM_MODE) {
+            if cpu_flag.contains(CPUControlFlags::STM_BEGIN) {
+                self.pending_stm.clear();
+            }
+            self.pending_stm.push(body);
+            if cpu_flag.contains(CPUControlFlags::STM_END) {
+                self.drives = std::mem::take(&mut self.pending_stm);
+            }
+        } else if fpga_flag.contains(FPGAControlFlags::LEGACY_MODE) {
+            self.drives = vec![body];
+        } else {
+            // duty and phase are sent in separate frames in normal mode
+            let is_duty = cpu_flag.contains(CPUControlFlags::IS_DUTY);
+            self.drives.retain(|d| {
+                let h = d.header();
+                !h.fpga_flag.contains(FPGAControlFlags::LEGACY_MODE)
+                    && !h.fpga_flag.contains(FPGAControlFlags::STM_MODE)
+                    && h.cpu_flag.contains(CPUControlFlags::IS_DUTY) != is_duty
+            });
+            self.drives.push(body);
+        }
+    }
+
+    /// Return frames to restore the devices in order of sending
+    pub fn frames(&self) -> Vec<TxDatagram> {
+        self.sync
+            .iter()
+            .chain(self.silencer.iter())
+            .chain(self.modulation.iter())
+            .chain(self.mod_delay.iter())
+            .chain(self.drives.iter())
+            .cloned()
+            .collect()
+    }
+
+    fn header_part(tx: &TxDatagram) -> TxDatagram {
+        let mut tx = tx.clone();
+        autd3_core::null_body(&mut tx);
+        tx
+    }
+
+    fn body_part(tx: &TxDatagram) -> TxDatagram {
+        let mut tx = tx.clone();
+        autd3_core::null_header(tx.header().msg_id, &mut tx);
+        tx
+    }
+}
@@ -11,11 +11,43 @@
  *
  */
 
+#[cfg(feature = "async")]
+mod async_controller;
 mod controller;
+mod dedup;
+mod engine;
 mod error;
+mod event;
 pub mod gain;
+mod history;
 pub mod modulation;
+mod monitor;
+mod msg_id;
+mod periodic;
+mod policy;
 pub mod prelude;
+mod progress;
+mod report;
+mod state;
+#[cfg(test)]
+mod test_utils;
+mod timeline;
+mod transaction;
+mod validate;
+mod watchdog;
 
+#[cfg(feature = "async")]
+pub use async_controller::AsyncController;
 pub use autd3_core;
 pub use controller::Controller;
+pub use error::AUTDError;
+pub use event::ControllerEvent;
+pub use monitor::{ThermalEvent, ThermalMonitor};
+pub use periodic::{DurationStats, FrameContext, PeriodHistogram, PeriodicStats};
+pub use policy::{Backoff, DropBehavior, FailureMode, SendPolicy, SyncPolicy};
+pub use progress::{CancellationToken, Progress};
+pub use report::{DeviceReport, SendReport};
+pub use state::{DeviceState, ModulationState, OutputMode};
+pub use timeline::{Timeline, TimelinePlayer};
+pub use transaction::{BodyQueue, HeaderQueue, Transaction};
+pub use validate::{validate, Validation};
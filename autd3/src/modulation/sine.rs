@@ -13,8 +13,10 @@
 
 use std::f64::consts::PI;
 
-use anyhow::Result;
-use autd3_core::modulation::{ModProps, Modulation};
+use autd3_core::{
+    error::AUTDInternalError,
+    modulation::{ModProps, Modulation},
+};
 use autd3_traits::Modulation;
 
 use num::integer::gcd;
@@ -58,7 +60,7 @@ impl Sine {
     }
 
     #[allow(clippy::unnecessary_wraps)]
-    fn calc(&mut self) -> Result<()> {
+    fn calc(&mut self) -> Result<(), AUTDInternalError> {
         let sf = self.sampling_freq() as usize;
 
         let freq = self.freq.clamp(1, sf / 2);
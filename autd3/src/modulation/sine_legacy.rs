@@ -13,8 +13,10 @@
 
 use std::f64::consts::PI;
 
-use anyhow::Result;
-use autd3_core::modulation::{ModProps, Modulation};
+use autd3_core::{
+    error::AUTDInternalError,
+    modulation::{ModProps, Modulation},
+};
 use autd3_traits::Modulation;
 
 /// Sine wave modulation in ultrasound amplitude
@@ -56,7 +58,7 @@ impl SineLegacy {
     }
 
     #[allow(clippy::unnecessary_wraps)]
-    fn calc(&mut self) -> Result<()> {
+    fn calc(&mut self) -> Result<(), AUTDInternalError> {
         let sf = self.sampling_freq();
 
         let freq = self
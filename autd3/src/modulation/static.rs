@@ -11,8 +11,10 @@
  *
  */
 
-use anyhow::Result;
-use autd3_core::modulation::{ModProps, Modulation};
+use autd3_core::{
+    error::AUTDInternalError,
+    modulation::{ModProps, Modulation},
+};
 use autd3_traits::Modulation;
 
 /// Sine wave modulation in ultrasound amplitude
@@ -32,7 +34,7 @@ impl Static {
     }
 
     #[allow(clippy::unnecessary_wraps)]
-    fn calc(&mut self) -> Result<()> {
+    fn calc(&mut self) -> Result<(), AUTDInternalError> {
         self.props.buffer.resize(2, self.duty);
 
         Ok(())
@@ -0,0 +1,214 @@
+/*
+ * File: monitor.rs
+ * Project: src
+ * Created Date: 18/10/2026
+ * Author: Shun Suzuki
+ * -----
+ * Last Modified: 18/10/2026
+ * Modified By: Shun Suzuki (suzuki@hapis.k.u-tokyo.ac.jp)
+ * -----
+ * Copyright (c) 2026 Shun Suzuki. All rights reserved.
+ *
+ */
+
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    thread::JoinHandle,
+    time::Duration,
+};
+
+use autd3_core::{link::Link, FPGAInfo, RxDatagram, MSG_BEGIN, MSG_END};
+
+use crate::controller::lock;
+
+/// Change of the thermal sensor state of a device
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ThermalEvent {
+    /// Index of the device
+    pub device: usize,
+    /// Whether the thermal sensor is asserted
+    pub asserted: bool,
+}
+
+/// Detector of changes of the thermal sensor states
+pub(crate) struct ThermalDetector {
+    asserted: Vec<bool>,
+}
+
+impl ThermalDetector {
+    pub fn new(num_devices: usize) -> Self {
+        Self {
+            asserted: vec![false; num_devices],
+        }
+    }
+
+    /// Return events of the devices whose sensor state changed
+    ///
+    /// # Arguments
+    ///
+    /// * `rx` - Received data
+    /// * `valid` - Whether the data of each device is up to date
+    ///
+    pub fn detect(
+        &mut self,
+        rx: &RxDatagram,
+        valid: impl Iterator<Item = bool>,
+    ) -> Vec<ThermalEvent> {
+        rx.messages()
+            .iter()
+            .zip(valid)
+            .enumerate()
+            // acks of clear and firmware info requests do not contain FPGA information
+            .filter(|(_, (msg, valid))| *valid && (MSG_BEGIN..=MSG_END).contains(&msg.msg_id))
+            .filter_map(|(device, (msg, _))| {
+                let asserted = FPGAInfo::from(msg).is_thermal_assert();
+                if asserted == self.asserted[device] {
+                    return None;
+                }
+                self.asserted[device] = asserted;
+                Some(ThermalEvent { device, asserted })
+            })
+            .collect()
+    }
+}
+
+/// Handle of the background thread polling FPGA information
+///
+/// The thread stops when the handle is dropped.
+pub struct ThermalMonitor {
+    running: Arc<AtomicBool>,
+    th: Option<JoinHandle<()>>,
+}
+
+impl ThermalMonitor {
+    pub(crate) fn start<L: Link + 'static, F: FnMut(ThermalEvent) + Send + 'static>(
+        link: Arc<Mutex<L>>,
+        num_devices: usize,
+        interval: Duration,
+        mut callback: F,
+    ) -> Self {
+        let running = Arc::new(AtomicBool::new(true));
+        let th = {
+            let running = running.clone();
+            std::thread::spawn(move || {
+                let mut rx = RxDatagram::new(num_devices);
+                let mut detector = ThermalDetector::new(num_devices);
+                while running.load(Ordering::Acquire) {
+                    let received = {
+                        let mut link = lock(&link);
+                        link.is_open() && link.receive(&mut rx).unwrap_or(false)
+                    };
+                    if received {
+                        detector
+                            .detect(&rx, std::iter::repeat(true))
+                            .into_iter()
+                            .for_each(&mut callback);
+                    }
+                    std::thread::sleep(interval);
+                }
+            })
+        };
+        Self {
+            running,
+            th: Some(th),
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.th.as_ref().is_some_and(|th| !th.is_finished())
+    }
+
+    /// Stop the monitor and wait for the thread to finish
+    pub fn stop(mut self) {
+        self.finish();
+    }
+
+    fn finish(&mut self) {
+        self.running.store(false, Ordering::Release);
+        if let Some(th) = self.th.take() {
+            let _ = th.join();
+        }
+    }
+}
+
+impl Drop for ThermalMonitor {
+    fn drop(&mut self) {
+        self.finish();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rx(msg_ids: &[u8], asserted: &[bool]) -> RxDatagram {
+        let mut rx = RxDatagram::new(msg_ids.len());
+        rx.messages_mut()
+            .iter_mut()
+            .zip(msg_ids.iter().zip(asserted.iter()))
+            .for_each(|(msg, (&id, &a))| {
+                msg.msg_id = id;
+                msg.ack = if a { 0x01 } else { 0x00 };
+            });
+        rx
+    }
+
+    #[test]
+    fn detect_only_changes() {
+        let mut detector = ThermalDetector::new(2);
+        let all = || std::iter::repeat(true);
+
+        let events = detector.detect(&rx(&[MSG_BEGIN; 2], &[false, true]), all());
+        assert_eq!(
+            events,
+            vec![ThermalEvent {
+                device: 1,
+                asserted: true
+            }]
+        );
+        assert!(detector
+            .detect(&rx(&[MSG_BEGIN; 2], &[false, true]), all())
+            .is_empty());
+
+        let events = detector.detect(&rx(&[MSG_BEGIN; 2], &[true, false]), all());
+        assert_eq!(
+            events,
+            vec![
+                ThermalEvent {
+                    device: 0,
+                    asserted: true
+                },
+                ThermalEvent {
+                    device: 1,
+                    asserted: false
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn ignore_invalid_data() {
+        let mut detector = ThermalDetector::new(2);
+
+        let events = detector.detect(
+            &rx(&[0x00, MSG_END], &[true, true]),
+            [true, false].into_iter(),
+        );
+        assert!(events.is_empty());
+
+        let events = detector.detect(
+            &rx(&[MSG_BEGIN, MSG_END], &[true, true]),
+            [false, true].into_iter(),
+        );
+        assert_eq!(
+            events,
+            vec![ThermalEvent {
+                device: 1,
+                asserted: true
+            }]
+        );
+    }
+}
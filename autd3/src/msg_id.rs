@@ -0,0 +1,89 @@
+/*
+ * File: msg_id.rs
+ * Project: src
+ * Created Date: 18/10/2026
+ * Author: Shun Suzuki
+ * -----
+ * Last Modified: 18/10/2026
+ * Modified By: Shun Suzuki (suzuki@hapis.k.u-tokyo.ac.jp)
+ * -----
+ * Copyright (c) 2026 Shun Suzuki. All rights reserved.
+ *
+ */
+
+use std::sync::atomic::{self, AtomicU8};
+
+use autd3_core::{MSG_BEGIN, MSG_END};
+
+/// Message ID allocator
+///
+/// Each controller owns its own allocator, so that controllers in the same process never share message IDs.
+pub(crate) struct MsgIdAllocator {
+    id: AtomicU8,
+}
+
+impl MsgIdAllocator {
+    pub fn new() -> Self {
+        Self {
+            id: AtomicU8::new(MSG_END),
+        }
+    }
+
+    /// Return next message ID in `MSG_BEGIN..=MSG_END`
+    pub fn next(&self) -> u8 {
+        let prev = self
+            .id
+            .fetch_update(atomic::Ordering::SeqCst, atomic::Ordering::SeqCst, |id| {
+                Some(Self::succ(id))
+            })
+            .unwrap();
+        Self::succ(prev)
+    }
+
+    fn succ(id: u8) -> u8 {
+        if id >= MSG_END {
+            MSG_BEGIN
+        } else {
+            id + 1
+        }
+    }
+}
+
+impl Default for MsgIdAllocator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn starts_at_msg_begin() {
+        let id = MsgIdAllocator::new();
+        assert_eq!(id.next(), MSG_BEGIN);
+        assert_eq!(id.next(), MSG_BEGIN + 1);
+    }
+
+    #[test]
+    fn wraps_around_without_reserved_ids() {
+        let id = MsgIdAllocator::new();
+        let ids = (0..2 * 256).map(|_| id.next()).collect::<Vec<_>>();
+        assert!(ids.iter().all(|id| (MSG_BEGIN..=MSG_END).contains(id)));
+
+        let end = ids.iter().position(|&id| id == MSG_END).unwrap();
+        assert_eq!(end, (MSG_END - MSG_BEGIN) as usize);
+        assert_eq!(ids[end + 1], MSG_BEGIN);
+    }
+
+    #[test]
+    fn allocators_are_independent() {
+        let a = MsgIdAllocator::new();
+        let b = MsgIdAllocator::new();
+        a.next();
+        a.next();
+        assert_eq!(b.next(), MSG_BEGIN);
+        assert_eq!(a.next(), MSG_BEGIN + 2);
+    }
+}
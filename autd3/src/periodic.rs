@@ -0,0 +1,244 @@
+/*
+ * File: periodic.rs
+ * Project: src
+ * Created Date: 18/10/2026
+ * Author: Shun Suzuki
+ * -----
+ * Last Modified: 18/10/2026
+ * Modified By: Shun Suzuki (suzuki@hapis.k.u-tokyo.ac.jp)
+ * -----
+ * Copyright (c) 2026 Shun Suzuki. All rights reserved.
+ *
+ */
+
+use std::time::{Duration, Instant};
+
+use autd3_core::timer::Waiter;
+
+/// Number of histogram bins per period
+const BINS_PER_PERIOD: u32 = 10;
+/// Histogram covers periods up to this multiple of the target period
+const MAX_PERIODS: u32 = 3;
+
+/// Context of a frame in [Controller::run_periodic](crate::Controller::run_periodic)
+#[derive(Clone, Copy, Debug)]
+pub struct FrameContext {
+    /// Index of the frame
+    pub index: usize,
+    /// Scheduled time of the frame since the loop started
+    pub time: Duration,
+    /// Number of deadlines missed so far
+    pub missed: usize,
+    stopped: bool,
+}
+
+impl FrameContext {
+    /// Stop the loop after sending the data of this frame
+    pub fn stop(&mut self) {
+        self.stopped = true;
+    }
+
+    pub fn is_stopped(&self) -> bool {
+        self.stopped
+    }
+}
+
+/// Minimum, maximum and mean of durations
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DurationStats {
+    count: usize,
+    min: Duration,
+    max: Duration,
+    total: Duration,
+}
+
+impl DurationStats {
+    fn add(&mut self, d: Duration) {
+        if self.count == 0 || d < self.min {
+            self.min = d;
+        }
+        self.max = self.max.max(d);
+        self.total += d;
+        self.count += 1;
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    pub fn min(&self) -> Duration {
+        self.min
+    }
+
+    pub fn max(&self) -> Duration {
+        self.max
+    }
+
+    pub fn mean(&self) -> Duration {
+        if self.count == 0 {
+            return Duration::ZERO;
+        }
+        self.total / self.count as u32
+    }
+}
+
+/// Histogram of actual periods
+///
+/// The `i`-th bin counts periods in `[i * bin_width, (i + 1) * bin_width)`, and the last bin also counts longer periods.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PeriodHistogram {
+    bin_width: Duration,
+    counts: Vec<usize>,
+}
+
+impl PeriodHistogram {
+    fn new(period: Duration) -> Self {
+        Self {
+            bin_width: (period / BINS_PER_PERIOD).max(Duration::from_nanos(1)),
+            counts: vec![0; (BINS_PER_PERIOD * MAX_PERIODS) as usize + 1],
+        }
+    }
+
+    fn add(&mut self, d: Duration) {
+        let i = (d.as_nanos() / self.bin_width.as_nanos()) as usize;
+        let last = self.counts.len() - 1;
+        self.counts[i.min(last)] += 1;
+    }
+
+    pub fn bin_width(&self) -> Duration {
+        self.bin_width
+    }
+
+    pub fn counts(&self) -> &[usize] {
+        &self.counts
+    }
+}
+
+/// Statistics of [Controller::run_periodic](crate::Controller::run_periodic)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PeriodicStats {
+    /// Number of sent frames
+    pub frames: usize,
+    /// Number of frames which finished sending after the next deadline
+    pub missed_deadlines: usize,
+    /// Actual intervals between the starts of consecutive frames
+    pub period: DurationStats,
+    pub period_histogram: PeriodHistogram,
+    /// Time to build and send the data of a frame
+    pub send_latency: DurationStats,
+}
+
+/// Schedule of periodic frames
+pub(crate) struct Scheduler {
+    period: Duration,
+    start: Instant,
+    deadline: Instant,
+    last: Option<Instant>,
+    ctx: FrameContext,
+    stats: PeriodicStats,
+}
+
+impl Scheduler {
+    /// Start the schedule
+    ///
+    /// `period` must not be zero, or [Scheduler::finish] never returns.
+    pub fn new(period: Duration) -> Self {
+        let start = Instant::now();
+        Self {
+            period,
+            start,
+            deadline: start,
+            last: None,
+            ctx: FrameContext {
+                index: 0,
+                time: Duration::ZERO,
+                missed: 0,
+                stopped: false,
+            },
+            stats: PeriodicStats {
+                frames: 0,
+                missed_deadlines: 0,
+                period: DurationStats::default(),
+                period_histogram: PeriodHistogram::new(period),
+                send_latency: DurationStats::default(),
+            },
+        }
+    }
+
+    /// Wait for the next frame, and return its context
+    pub fn wait<W: Waiter>(&mut self) -> &mut FrameContext {
+        W::wait_until(self.deadline);
+        let now = Instant::now();
+        if let Some(last) = self.last {
+            self.stats.period.add(now - last);
+            self.stats.period_histogram.add(now - last);
+        }
+        self.last = Some(now);
+        self.ctx.time = self.deadline - self.start;
+        &mut self.ctx
+    }
+
+    /// Record the frame started at the last [Scheduler::wait] as sent, and schedule the next frame
+    ///
+    /// Missed frames are skipped instead of being sent in a burst.
+    pub fn finish(&mut self) {
+        let now = Instant::now();
+        if let Some(last) = self.last {
+            self.stats.send_latency.add(now - last);
+        }
+        self.stats.frames += 1;
+        self.deadline += self.period;
+        if now > self.deadline {
+            self.stats.missed_deadlines += 1;
+            while self.deadline < now {
+                self.deadline += self.period;
+            }
+        }
+        self.ctx.index += 1;
+        self.ctx.missed = self.stats.missed_deadlines;
+    }
+
+    pub fn is_stopped(&self) -> bool {
+        self.ctx.stopped
+    }
+
+    pub fn stats(self) -> PeriodicStats {
+        self.stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use autd3_core::timer::NormalWaiter;
+
+    use super::*;
+
+    #[test]
+    fn finish_skips_missed_deadlines() {
+        let period = Duration::from_millis(20);
+        let mut scheduler = Scheduler::new(period);
+        scheduler.wait::<NormalWaiter>();
+        std::thread::sleep(period * 3);
+        scheduler.finish();
+        assert!(scheduler.deadline >= Instant::now());
+
+        let ctx = scheduler.wait::<NormalWaiter>();
+        assert_eq!(ctx.index, 1);
+        assert_eq!(ctx.missed, 1);
+        assert!(ctx.time >= period * 3);
+
+        let stats = scheduler.stats();
+        assert_eq!(stats.frames, 1);
+        assert_eq!(stats.missed_deadlines, 1);
+        assert_eq!(stats.period.count(), 1);
+    }
+
+    #[test]
+    fn histogram_saturates_at_last_bin() {
+        let mut histogram = PeriodHistogram::new(Duration::from_millis(1));
+        histogram.add(Duration::from_micros(150));
+        histogram.add(Duration::from_secs(1));
+        assert_eq!(histogram.counts()[1], 1);
+        assert_eq!(*histogram.counts().last().unwrap(), 1);
+    }
+}
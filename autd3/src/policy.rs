@@ -0,0 +1,213 @@
+/*
+ * File: policy.rs
+ * Project: src
+ * Created Date: 18/10/2026
+ * Author: Shun Suzuki
+ * -----
+ * Last Modified: 18/10/2026
+ * Modified By: Shun Suzuki (suzuki@hapis.k.u-tokyo.ac.jp)
+ * -----
+ * Copyright (c) 2026 Shun Suzuki. All rights reserved.
+ *
+ */
+
+use std::time::Duration;
+
+/// Behavior when devices do not acknowledge a frame
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FailureMode {
+    /// Abort the operation and return [AUTDError::SendFailed](crate::AUTDError::SendFailed)
+    FailFast,
+    /// Continue to send the remaining frames, and record the failure in [SendReport](crate::SendReport)
+    BestEffort,
+}
+
+/// Wait before resending a frame
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Backoff {
+    Constant(Duration),
+    /// Double the wait for every retry up to `max`
+    Exponential {
+        initial: Duration,
+        max: Duration,
+    },
+}
+
+impl Backoff {
+    /// Return the wait before `retry`-th resend (0-indexed)
+    pub fn delay(&self, retry: usize) -> Duration {
+        match *self {
+            Self::Constant(d) => d,
+            Self::Exponential { initial, max } => initial
+                .checked_mul(1 << retry.min(31))
+                .map_or(max, |d| d.min(max)),
+        }
+    }
+}
+
+/// Number of EtherCAT cycles to wait for acknowledgements of control frames by default
+const CONTROL_TRIALS: u32 = 200;
+
+/// Policy to wait for acknowledgements of each frame
+///
+/// The policy is applied to all controller operations.
+/// By default, acknowledgements are checked only for clear, synchronize and firmware information requests,
+/// and data frames are sent without waiting, as in `check_trials = 0` of former versions.
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use autd3::prelude::*;
+/// use autd3_link_debug::Debug;
+///
+/// # fn main() -> Result<(), AUTDError> {
+/// let mut geometry = GeometryBuilder::new().legacy_mode().build();
+/// geometry.add_device(Vector3::zeros(), Vector3::zeros());
+///
+/// let mut autd = Controller::open(geometry, Debug::new())?;
+/// assert_eq!(autd.clear()?.devices()[0].trials, 1);
+/// assert_eq!(autd.send(SilencerConfig::default())?.devices()[0].trials, 0);
+///
+/// autd.send_policy = SendPolicy {
+///     retries: 3,
+///     backoff: Backoff::Exponential {
+///         initial: Duration::from_millis(1),
+///         max: Duration::from_millis(10),
+///     },
+///     mode: FailureMode::FailFast,
+///     ..SendPolicy::checked(Duration::from_millis(20))
+/// };
+/// assert_eq!(autd.send(SilencerConfig::new(20, 4096))?.devices()[0].trials, 1);
+///
+/// autd.close()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SendPolicy {
+    /// Timeout to wait for acknowledgements of a data frame. If zero, acknowledgements of data frames are not checked.
+    pub timeout: Duration,
+    /// Timeout to wait for acknowledgements of clear, synchronize and firmware information requests, which are always checked.
+    /// If None, 200 cycles of `send_interval` are waited.
+    pub control_timeout: Option<Duration>,
+    /// Number of resends of a frame after timeout
+    pub retries: usize,
+    pub backoff: Backoff,
+    pub mode: FailureMode,
+}
+
+impl SendPolicy {
+    /// Check acknowledgements of data frames with the timeout
+    pub fn checked(timeout: Duration) -> Self {
+        Self {
+            timeout,
+            ..Default::default()
+        }
+    }
+
+    /// Return true if acknowledgements of data frames are checked
+    pub fn is_checked(&self) -> bool {
+        !self.timeout.is_zero()
+    }
+
+    /// Return the timeout of control frames sent every `interval`
+    pub(crate) fn control_frame_timeout(&self, interval: Duration) -> Duration {
+        self.control_timeout.unwrap_or(interval * CONTROL_TRIALS)
+    }
+}
+
+impl Default for SendPolicy {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::ZERO,
+            control_timeout: None,
+            retries: 0,
+            backoff: Backoff::Constant(Duration::ZERO),
+            mode: FailureMode::BestEffort,
+        }
+    }
+}
+
+/// Behavior when a controller is dropped without being closed
+///
+/// ```
+/// use autd3::prelude::*;
+/// use autd3_link_debug::Debug;
+///
+/// # fn main() -> Result<(), AUTDError> {
+/// let mut geometry = GeometryBuilder::new().build();
+/// geometry.add_device(Vector3::zeros(), Vector3::zeros());
+///
+/// let link = Debug::new();
+/// let emulator = link.emulator();
+/// {
+///     let mut autd = Controller::open(geometry, link)?;
+///     autd.clear()?;
+///     autd.synchronize()?;
+///     autd.drop_behavior = DropBehavior::EmergencyStop;
+///     let center = autd.geometry().center();
+///     autd.send((Static::new(0xFF), Focus::new(center)))?;
+/// }
+///
+/// let emulator = emulator.lock().unwrap();
+/// assert!(emulator.fpga(0).drives()[0].0.iter().all(|d| d.duty == 0));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DropBehavior {
+    /// Stop outputting and close the link
+    #[default]
+    Close,
+    /// Stop outputting without the silencer and close the link
+    EmergencyStop,
+    /// Leave the devices as they are
+    Keep,
+}
+
+/// Behavior when normal mode data would be sent with transducer cycles different from the last synchronized ones
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SyncPolicy {
+    /// Return [AUTDError::NotSynchronized](crate::AUTDError::NotSynchronized) without sending the data
+    #[default]
+    Error,
+    /// Synchronize the cycles before sending the data
+    AutoSync,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exponential_backoff_is_capped() {
+        let backoff = Backoff::Exponential {
+            initial: Duration::from_millis(1),
+            max: Duration::from_millis(5),
+        };
+        let delays = (0..5).map(|i| backoff.delay(i)).collect::<Vec<_>>();
+        assert_eq!(delays, [1, 2, 4, 5, 5].map(Duration::from_millis).to_vec());
+        assert_eq!(backoff.delay(usize::MAX), Duration::from_millis(5));
+    }
+
+    #[test]
+    fn control_frames_wait_200_cycles_by_default() {
+        let policy = SendPolicy::default();
+        assert!(!policy.is_checked());
+        let interval = Duration::from_micros(500);
+        assert_eq!(
+            policy.control_frame_timeout(interval),
+            Duration::from_millis(100)
+        );
+
+        let policy = SendPolicy {
+            control_timeout: Some(Duration::from_millis(1)),
+            ..SendPolicy::checked(Duration::from_millis(10))
+        };
+        assert!(policy.is_checked());
+        assert_eq!(
+            policy.control_frame_timeout(interval),
+            Duration::from_millis(1)
+        );
+    }
+}
@@ -11,15 +11,37 @@
  *
  */
 
-pub use crate::{controller::Controller, gain::*, modulation::*};
+pub use crate::{
+    controller::Controller,
+    error::AUTDError,
+    event::ControllerEvent,
+    gain::*,
+    modulation::*,
+    monitor::{ThermalEvent, ThermalMonitor},
+    periodic::{FrameContext, PeriodicStats},
+    policy::{Backoff, DropBehavior, FailureMode, SendPolicy, SyncPolicy},
+    progress::{CancellationToken, Progress},
+    report::{DeviceReport, SendReport},
+    state::{DeviceState, ModulationState, OutputMode},
+    timeline::{Timeline, TimelinePlayer},
+    transaction::Transaction,
+    validate::{validate, Validation},
+};
+
+#[cfg(feature = "async")]
+pub use crate::async_controller::AsyncController;
+
+#[cfg(feature = "async")]
+pub use autd3_core::link::AsyncLink;
 
 pub use autd3_core::{
     delay::ModDelay,
     geometry::{
-        Amplitudes, Geometry, GeometryBuilder, LegacyTransducer, NormalPhaseTransducer,
-        NormalTransducer, Transducer, Vector3,
+        AUTD3Layout, Amplitudes, Calibration, DeviceLayout, Geometry, GeometryBuilder, GridLayout,
+        LegacyTransducer, LengthUnit, NormalPhaseTransducer, NormalTransducer, Rotation,
+        Transducer, Vector3,
     },
-    link::Link,
+    link::{DynLink, Link},
     silencer_config::SilencerConfig,
     stm::{GainSTM, PointSTM, STM},
     Mode, DEVICE_HEIGHT, DEVICE_WIDTH, NUM_TRANS_IN_UNIT, NUM_TRANS_X, NUM_TRANS_Y,
@@ -0,0 +1,194 @@
+/*
+ * File: report.rs
+ * Project: src
+ * Created Date: 18/10/2026
+ * Author: Shun Suzuki
+ * -----
+ * Last Modified: 18/10/2026
+ * Modified By: Shun Suzuki (suzuki@hapis.k.u-tokyo.ac.jp)
+ * -----
+ * Copyright (c) 2026 Shun Suzuki. All rights reserved.
+ *
+ */
+
+use std::{
+    fmt,
+    time::{Duration, Instant},
+};
+
+use autd3_core::RxDatagram;
+
+/// Acknowledgement result of a device
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DeviceReport {
+    /// Whether the device acknowledged all checked frames
+    pub acked: bool,
+    /// Total number of trials to receive acknowledgements
+    pub trials: usize,
+    /// Total time from sending frames to receiving acknowledgements
+    pub latency: Duration,
+}
+
+impl Default for DeviceReport {
+    fn default() -> Self {
+        Self {
+            acked: true,
+            trials: 0,
+            latency: Duration::ZERO,
+        }
+    }
+}
+
+/// Acknowledgement results of a send operation
+///
+/// Frames sent without ack checking do not affect the results.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SendReport {
+    devices: Vec<DeviceReport>,
+    frames: usize,
+    unchecked: usize,
+}
+
+impl SendReport {
+    pub(crate) fn new(num_devices: usize) -> Self {
+        Self {
+            devices: vec![DeviceReport::default(); num_devices],
+            frames: 0,
+            unchecked: 0,
+        }
+    }
+
+    /// Return results of each device
+    pub fn devices(&self) -> &[DeviceReport] {
+        &self.devices
+    }
+
+    /// Return the number of sent frames
+    pub fn frames(&self) -> usize {
+        self.frames
+    }
+
+    /// Return true if all devices acknowledged all checked frames
+    pub fn is_success(&self) -> bool {
+        self.devices.iter().all(|d| d.acked)
+    }
+
+    /// Return the number of frames sent without ack checking
+    pub fn unchecked_frames(&self) -> usize {
+        self.unchecked
+    }
+
+    /// Return true if all frames were checked and all devices acknowledged them
+    pub fn is_confirmed(&self) -> bool {
+        self.unchecked == 0 && self.is_success()
+    }
+
+    /// Return indices of devices which did not acknowledge
+    pub fn failed_devices(&self) -> impl Iterator<Item = usize> + '_ {
+        self.devices
+            .iter()
+            .enumerate()
+            .filter(|(_, d)| !d.acked)
+            .map(|(i, _)| i)
+    }
+
+    /// Merge results of subsequent send operation
+    pub fn merge(mut self, other: SendReport) -> SendReport {
+        if self.devices.len() < other.devices.len() {
+            self.devices
+                .resize(other.devices.len(), DeviceReport::default());
+        }
+        self.devices
+            .iter_mut()
+            .zip(other.devices.iter())
+            .for_each(|(d, o)| {
+                d.acked &= o.acked;
+                d.trials += o.trials;
+                d.latency += o.latency;
+            });
+        self.frames += other.frames;
+        self.unchecked += other.unchecked;
+        self
+    }
+
+    pub(crate) fn add_unchecked_frame(&mut self) {
+        self.frames += 1;
+        self.unchecked += 1;
+    }
+
+    pub(crate) fn add_frame(&mut self, ack: AckTracker) {
+        self.frames += 1;
+        let elapsed = ack.start.elapsed();
+        self.devices
+            .iter_mut()
+            .zip(ack.acks.iter())
+            .for_each(|(d, a)| match a {
+                Some((trials, latency)) => {
+                    d.trials += trials;
+                    d.latency += *latency;
+                }
+                None => {
+                    d.acked = false;
+                    d.trials += ack.trials;
+                    d.latency += elapsed;
+                }
+            });
+    }
+}
+
+impl fmt::Display for SendReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({} frames)", self.is_success(), self.frames)?;
+        for (i, d) in self.devices.iter().enumerate().filter(|(_, d)| !d.acked) {
+            write!(
+                f,
+                "\n  {}: no ack after {} trials ({:?})",
+                i, d.trials, d.latency
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Track acknowledgements of each device for a frame
+pub(crate) struct AckTracker {
+    msg_id: u8,
+    start: Instant,
+    trials: usize,
+    acks: Vec<Option<(usize, Duration)>>,
+}
+
+impl AckTracker {
+    pub fn new(msg_id: u8, num_devices: usize) -> Self {
+        Self {
+            msg_id,
+            start: Instant::now(),
+            trials: 0,
+            acks: vec![None; num_devices],
+        }
+    }
+
+    /// Update with received data, and return true if all devices acknowledged
+    pub fn update(&mut self, rx: Option<&RxDatagram>) -> bool {
+        self.trials += 1;
+        if let Some(rx) = rx {
+            let elapsed = self.start.elapsed();
+            let (msg_id, trials) = (self.msg_id, self.trials);
+            self.acks
+                .iter_mut()
+                .zip(rx.messages())
+                .filter(|(a, msg)| a.is_none() && msg.msg_id == msg_id)
+                .for_each(|(a, _)| *a = Some((trials, elapsed)));
+        }
+        self.is_acked()
+    }
+
+    pub fn is_acked(&self) -> bool {
+        self.acks.iter().all(Option::is_some)
+    }
+
+    /// Return whether each device acknowledged
+    pub fn acked_devices(&self) -> impl Iterator<Item = bool> + '_ {
+        self.acks.iter().map(Option::is_some)
+    }
+}
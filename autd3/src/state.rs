@@ -0,0 +1,154 @@
+/*
+ * File: state.rs
+ * Project: src
+ * Created Date: 18/10/2026
+ * Author: Shun Suzuki
+ * -----
+ * Last Modified: 18/10/2026
+ * Modified By: Shun Suzuki (suzuki@hapis.k.u-tokyo.ac.jp)
+ * -----
+ * Copyright (c) 2026 Shun Suzuki. All rights reserved.
+ *
+ */
+
+use autd3_core::{
+    CPUControlFlags, FPGAControlFlags, TxDatagram, MSG_BEGIN, MSG_CLEAR, MSG_END, NUM_TRANS_IN_UNIT,
+};
+
+/// Output mode of a device
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutputMode {
+    Normal,
+    PointSTM,
+    GainSTM,
+}
+
+/// Modulation loaded on a device
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ModulationState {
+    pub buffer: Vec<u8>,
+    pub freq_div: u32,
+}
+
+/// Last confirmed state of a device
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DeviceState {
+    pub legacy_mode: bool,
+    pub output: OutputMode,
+    pub force_fan: bool,
+    pub modulation: ModulationState,
+    pub silencer_step: u16,
+    pub silencer_cycle: u16,
+    pub mod_delays: [u16; NUM_TRANS_IN_UNIT],
+    /// Ultrasound cycles sent by synchronize, or None if not synchronized since clear
+    pub cycles: Option<[u16; NUM_TRANS_IN_UNIT]>,
+}
+
+impl DeviceState {
+    /// State right after clear
+    fn cleared() -> Self {
+        Self {
+            legacy_mode: true,
+            output: OutputMode::Normal,
+            force_fan: false,
+            modulation: ModulationState {
+                buffer: vec![0x00; 2],
+                freq_div: 40960,
+            },
+            silencer_step: 10,
+            silencer_cycle: 4096,
+            mod_delays: [0x0000; NUM_TRANS_IN_UNIT],
+            cycles: None,
+        }
+    }
+
+    pub fn is_synchronized(&self) -> bool {
+        self.cycles.is_some()
+    }
+}
+
+/// Mirror of device states, updated with acknowledged frames
+pub(crate) struct StateMirror {
+    devices: Vec<Option<DeviceState>>,
+    pending_mod: Vec<Option<ModulationState>>,
+}
+
+impl StateMirror {
+    pub fn new(num_devices: usize) -> Self {
+        Self {
+            devices: vec![None; num_devices],
+            pending_mod: vec![None; num_devices],
+        }
+    }
+
+    pub fn devices(&self) -> &[Option<DeviceState>] {
+        &self.devices
+    }
+
+    /// Forget the states, e.g., after the devices were changed without acknowledgements
+    pub fn invalidate(&mut self) {
+        self.devices.iter_mut().for_each(|d| *d = None);
+        self.pending_mod.iter_mut().for_each(|m| *m = None);
+    }
+
+    /// Apply a frame to the devices which acknowledged it
+    pub fn update(&mut self, tx: &TxDatagram, acked: impl Iterator<Item = bool>) {
+        let header = tx.header();
+        acked
+            .enumerate()
+            .filter(|&(_, acked)| acked)
+            .for_each(|(i, _)| {
+                if header.msg_id == MSG_CLEAR {
+                    self.devices[i] = Some(DeviceState::cleared());
+                    self.pending_mod[i] = None;
+                    return;
+                }
+                if !(MSG_BEGIN..=MSG_END).contains(&header.msg_id) {
+                    return;
+                }
+                let state = match &mut self.devices[i] {
+                    Some(state) => state,
+                    None => return,
+                };
+
+                state.legacy_mode = header.fpga_flag.contains(FPGAControlFlags::LEGACY_MODE);
+                state.force_fan = header.fpga_flag.contains(FPGAControlFlags::FORCE_FAN);
+                state.output = if !header.fpga_flag.contains(FPGAControlFlags::STM_MODE) {
+                    OutputMode::Normal
+                } else if header.fpga_flag.contains(FPGAControlFlags::STM_GAIN_MODE) {
+                    OutputMode::GainSTM
+                } else {
+                    OutputMode::PointSTM
+                };
+
+                if header.cpu_flag.contains(CPUControlFlags::MOD) {
+                    let size = header.size as usize;
+                    if header.cpu_flag.contains(CPUControlFlags::MOD_BEGIN) {
+                        self.pending_mod[i] = Some(ModulationState {
+                            buffer: header.mod_head().data[..size].to_vec(),
+                            freq_div: header.mod_head().freq_div,
+                        });
+                    } else if let Some(m) = &mut self.pending_mod[i] {
+                        m.buffer.extend_from_slice(&header.mod_body().data[..size]);
+                    }
+                    if header.cpu_flag.contains(CPUControlFlags::MOD_END) {
+                        if let Some(m) = self.pending_mod[i].take() {
+                            state.modulation = m;
+                        }
+                    }
+                } else if header.cpu_flag.contains(CPUControlFlags::CONFIG_SILENCER) {
+                    state.silencer_step = header.silencer_header().step;
+                    state.silencer_cycle = header.silencer_header().cycle;
+                } else if header.cpu_flag.contains(CPUControlFlags::CONFIG_SYNC) {
+                    state.cycles = Some(tx.body()[i].data);
+                    return;
+                }
+
+                if header.cpu_flag.contains(CPUControlFlags::WRITE_BODY)
+                    && header.cpu_flag.contains(CPUControlFlags::MOD_DELAY)
+                {
+                    state.mod_delays = tx.body()[i].data;
+                }
+            });
+    }
+}
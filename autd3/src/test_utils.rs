@@ -0,0 +1,164 @@
+/*
+ * File: test_utils.rs
+ * Project: src
+ * Created Date: 18/10/2026
+ * Author: Shun Suzuki
+ * -----
+ * Last Modified: 18/10/2026
+ * Modified By: Shun Suzuki (suzuki@hapis.k.u-tokyo.ac.jp)
+ * -----
+ * Copyright (c) 2026 Shun Suzuki. All rights reserved.
+ *
+ */
+
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+use autd3_core::{
+    error::AUTDInternalError,
+    geometry::{
+        Geometry, GeometryBuilder, LegacyTransducer, NormalTransducer, Transducer, Vector3,
+    },
+    link::{Link, LinkEvent},
+    RxDatagram, TxDatagram, MSG_END,
+};
+use autd3_firmware_emulator::Emulator;
+use autd3_link_debug::Debug;
+
+use crate::controller::Controller;
+
+/// Record of a [TestLink]
+#[derive(Default)]
+pub(crate) struct Probe {
+    /// Frames passed to the link in order, including lost ones
+    pub sent: Vec<TxDatagram>,
+    /// Number of following frames lost before reaching the devices
+    pub lost: usize,
+    /// Devices which never acknowledge
+    pub muted: Vec<usize>,
+}
+
+/// Handles to inspect and control a [TestLink] after it is moved into a controller
+pub(crate) struct Handles {
+    pub probe: Arc<Mutex<Probe>>,
+    pub emulator: Arc<Mutex<Emulator>>,
+    pub events: Arc<Mutex<Vec<LinkEvent>>>,
+}
+
+impl Handles {
+    pub fn probe(&self) -> MutexGuard<'_, Probe> {
+        self.probe.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    pub fn emulator(&self) -> MutexGuard<'_, Emulator> {
+        self.emulator.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    pub fn push_event(&self, event: LinkEvent) {
+        self.events
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(event);
+    }
+
+    /// Return frames sent after the first `skip` frames
+    pub fn sent_after(&self, skip: usize) -> Vec<TxDatagram> {
+        self.probe().sent[skip..].to_vec()
+    }
+
+    pub fn num_sent(&self) -> usize {
+        self.probe().sent.len()
+    }
+}
+
+/// Debug link which records sent frames and loses frames or acknowledgements on demand
+pub(crate) struct TestLink {
+    link: Debug,
+    probe: Arc<Mutex<Probe>>,
+    is_open: bool,
+}
+
+impl TestLink {
+    pub fn new() -> Self {
+        Self {
+            link: Debug::new(),
+            probe: Arc::new(Mutex::new(Probe::default())),
+            is_open: false,
+        }
+    }
+
+    pub fn handles(&self) -> Handles {
+        Handles {
+            probe: self.probe.clone(),
+            emulator: self.link.emulator(),
+            events: self.link.events(),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Probe> {
+        self.probe.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Link for TestLink {
+    fn open<T: Transducer>(&mut self, geometry: &Geometry<T>) -> Result<(), AUTDInternalError> {
+        self.link.open(geometry)?;
+        self.is_open = true;
+        Ok(())
+    }
+
+    fn close(&mut self) -> Result<(), AUTDInternalError> {
+        self.is_open = false;
+        self.link.close()
+    }
+
+    fn send(&mut self, tx: &TxDatagram) -> Result<bool, AUTDInternalError> {
+        let lost = {
+            let mut probe = self.lock();
+            probe.sent.push(tx.clone());
+            let lost = probe.lost > 0;
+            probe.lost = probe.lost.saturating_sub(1);
+            lost
+        };
+        if lost {
+            return Ok(true);
+        }
+        self.link.send(tx)
+    }
+
+    fn receive(&mut self, rx: &mut RxDatagram) -> Result<bool, AUTDInternalError> {
+        self.link.receive(rx)?;
+        self.lock().muted.iter().for_each(|&i| {
+            rx.messages_mut()[i].msg_id = MSG_END + 1;
+        });
+        Ok(true)
+    }
+
+    fn is_open(&self) -> bool {
+        self.is_open
+    }
+
+    fn poll_events(&mut self) -> Vec<LinkEvent> {
+        self.link.poll_events()
+    }
+}
+
+pub(crate) fn legacy_geometry(num_devices: usize) -> Geometry<LegacyTransducer> {
+    let mut geometry = GeometryBuilder::new().legacy_mode().build();
+    (0..num_devices).for_each(|_| geometry.add_device(Vector3::zeros(), Vector3::zeros()));
+    geometry
+}
+
+pub(crate) fn normal_geometry(num_devices: usize) -> Geometry<NormalTransducer> {
+    let mut geometry = GeometryBuilder::new().build();
+    (0..num_devices).for_each(|_| geometry.add_device(Vector3::zeros(), Vector3::zeros()));
+    geometry
+}
+
+/// Open a controller with [TestLink] and clear the devices
+pub(crate) fn open<T: Transducer>(geometry: Geometry<T>) -> (Controller<TestLink, T>, Handles) {
+    let link = TestLink::new();
+    let handles = link.handles();
+    let mut autd = Controller::open(geometry, link).unwrap();
+    autd.clear().unwrap();
+    (autd, handles)
+}
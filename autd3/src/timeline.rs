@@ -0,0 +1,416 @@
+/*
+ * File: timeline.rs
+ * Project: src
+ * Created Date: 18/10/2026
+ * Author: Shun Suzuki
+ * -----
+ * Last Modified: 18/10/2026
+ * Modified By: Shun Suzuki (suzuki@hapis.k.u-tokyo.ac.jp)
+ * -----
+ * Copyright (c) 2026 Shun Suzuki. All rights reserved.
+ *
+ */
+
+use std::time::{Duration, Instant};
+
+use autd3_core::{
+    error::AUTDInternalError,
+    geometry::{Geometry, Transducer},
+    interface::{DatagramBody, DatagramHeader, Sendable},
+    link::Link,
+    timer::{HighPrecisionWaiter, NormalWaiter, Waiter},
+    TxDatagram,
+};
+
+use crate::{controller::Controller, engine::pack_frames, error::AUTDError, report::SendReport};
+
+struct Entry<'a, T: Transducer> {
+    at: Duration,
+    header: Box<dyn DatagramHeader + 'a>,
+    body: Box<dyn DatagramBody<T> + 'a>,
+    frames: Option<Vec<TxDatagram>>,
+}
+
+impl<'a, T: Transducer> Entry<'a, T> {
+    /// Pack all frames of the entry in advance
+    fn prebuild(&mut self, geometry: &Geometry<T>) -> Result<(), AUTDInternalError> {
+        if self.frames.is_some() {
+            return Ok(());
+        }
+        self.frames = Some(pack_frames(
+            &mut self.header.as_mut(),
+            &mut self.body.as_mut(),
+            geometry,
+        )?);
+        Ok(())
+    }
+}
+
+/// Time-stamped sequence of gains, modulations, STMs and silencer configs
+///
+/// Entries are played by [TimelinePlayer]. Entries with the same time are sent in order of addition.
+pub struct Timeline<'a, T: Transducer> {
+    entries: Vec<Entry<'a, T>>,
+    duration: Option<Duration>,
+}
+
+impl<'a, T: Transducer> Timeline<'a, T> {
+    pub fn new() -> Self {
+        Self {
+            entries: vec![],
+            duration: None,
+        }
+    }
+
+    /// Add data to the timeline
+    ///
+    /// # Arguments
+    ///
+    /// * `at` - Time to send the data from the start of the timeline
+    /// * `s` - Header, body, or tuple of header and body
+    ///
+    pub fn add<S: Sendable<T>>(mut self, at: Duration, s: S) -> Self
+    where
+        S::H: 'a,
+        S::B: 'a,
+    {
+        let (header, body) = s.operation();
+        let idx = self.entries.partition_point(|e| e.at <= at);
+        self.entries.insert(
+            idx,
+            Entry {
+                at,
+                header: Box::new(header),
+                body: Box::new(body),
+                frames: None,
+            },
+        );
+        self
+    }
+
+    /// Set the length of the timeline, which is the time of the last entry by default
+    ///
+    /// # Arguments
+    ///
+    /// * `duration` - Length of the timeline. When looping, the timeline restarts after this.
+    ///
+    pub fn with_duration(mut self, duration: Duration) -> Self {
+        self.duration = Some(duration);
+        self
+    }
+
+    pub fn duration(&self) -> Duration {
+        self.duration
+            .unwrap_or_else(|| self.entries.last().map_or(Duration::ZERO, |e| e.at))
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl<'a, T: Transducer> Default for Timeline<'a, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Player which sends entries of [Timeline] to the controller at their times
+///
+/// Entries are packed into frames in advance when their times come within `lookahead`,
+/// so that the calculation of gains does not delay the uploads.
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use autd3::prelude::*;
+/// use autd3_link_debug::Debug;
+///
+/// # fn main() -> Result<(), AUTDError> {
+/// let mut geometry = GeometryBuilder::new().legacy_mode().build();
+/// geometry.add_device(Vector3::zeros(), Vector3::zeros());
+///
+/// let link = Debug::new();
+/// let emulator = link.emulator();
+/// let mut autd = Controller::open(geometry, link)?;
+/// autd.clear()?;
+/// autd.synchronize()?;
+///
+/// let center = autd.geometry().center();
+/// let mut stm = PointSTM::new();
+/// (0..100).try_for_each(|i| {
+///     let theta = 2. * std::f64::consts::PI * i as f64 / 100.;
+///     stm.add(center + 30. * Vector3::new(theta.cos(), theta.sin(), 0.), 0)
+/// })?;
+///
+/// let timeline = Timeline::new()
+///     .add(Duration::ZERO, (Sine::new(150), Focus::new(center)))
+///     .add(Duration::from_millis(15), Bessel::new(center, Vector3::z(), 18. / 180. * 3.14))
+///     .add(Duration::from_millis(30), stm);
+///
+/// let mut player = TimelinePlayer::new(timeline);
+/// player.run(&mut autd)?;
+/// assert!(player.is_finished());
+/// assert!(emulator.lock().unwrap().fpga(0).is_stm_mode());
+///
+/// // play from the middle again
+/// player.seek(Duration::from_millis(20));
+/// player.run(&mut autd)?;
+///
+/// autd.close()?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct TimelinePlayer<'a, T: Transducer> {
+    timeline: Timeline<'a, T>,
+    next: usize,
+    origin: Option<Instant>,
+    position: Duration,
+    /// Restart the timeline after its duration
+    pub looping: bool,
+    /// Entries within this time ahead of the current position are packed in advance
+    pub lookahead: Duration,
+}
+
+impl<'a, T: Transducer> TimelinePlayer<'a, T> {
+    /// Create a player paused at the start of the timeline
+    pub fn new(timeline: Timeline<'a, T>) -> Self {
+        Self {
+            timeline,
+            next: 0,
+            origin: None,
+            position: Duration::ZERO,
+            looping: false,
+            lookahead: Duration::from_millis(500),
+        }
+    }
+
+    pub fn timeline(&self) -> &Timeline<'a, T> {
+        &self.timeline
+    }
+
+    pub fn play(&mut self) {
+        if self.origin.is_none() {
+            self.origin = Some(Instant::now() - self.position);
+        }
+    }
+
+    pub fn pause(&mut self) {
+        self.position = self.position();
+        self.origin = None;
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.origin.is_some()
+    }
+
+    /// Return the current position in the timeline
+    pub fn position(&self) -> Duration {
+        self.origin.map_or(self.position, |o| o.elapsed())
+    }
+
+    /// Move to the position in the timeline
+    ///
+    /// The entries at the time of the last entry at or before the position are sent again on the next update, but earlier entries are not.
+    /// To reproduce the output at the position, make such entries contain the whole data, e.g., a tuple of modulation and gain.
+    ///
+    /// # Arguments
+    ///
+    /// * `position` - Time from the start of the timeline
+    ///
+    pub fn seek(&mut self, position: Duration) {
+        self.position = position;
+        if self.origin.is_some() {
+            self.origin = Some(Instant::now() - position);
+        }
+        let entries = &self.timeline.entries;
+        self.next = match entries.partition_point(|e| e.at <= position) {
+            0 => 0,
+            end => {
+                let at = entries[end - 1].at;
+                entries.partition_point(|e| e.at < at)
+            }
+        };
+    }
+
+    /// Return true if all entries have been sent and the player does not loop
+    pub fn is_finished(&self) -> bool {
+        !self.looping && self.next == self.timeline.entries.len()
+    }
+
+    /// Send the entries whose times have come, and pack the upcoming entries in advance
+    ///
+    /// Call this periodically while playing, or use [TimelinePlayer::run].
+    ///
+    /// # Arguments
+    ///
+    /// * `autd` - Controller to send the entries
+    ///
+    pub fn update<L: Link>(
+        &mut self,
+        autd: &mut Controller<L, T>,
+    ) -> Result<SendReport, AUTDError> {
+        let mut report = SendReport::new(autd.geometry().num_devices());
+        if !self.is_playing() {
+            return Ok(report);
+        }
+
+        loop {
+            let position = self.position();
+            while let Some(entry) = self.timeline.entries.get_mut(self.next) {
+                if entry.at > position {
+                    break;
+                }
+                entry.prebuild(autd.geometry())?;
+                report = report.merge(autd.send_packed(entry.frames.as_deref().unwrap_or(&[]))?);
+                self.next += 1;
+            }
+
+            let duration = self.timeline.duration();
+            if !self.looping
+                || duration.is_zero()
+                || self.next < self.timeline.entries.len()
+                || position < duration
+            {
+                break;
+            }
+            if let Some(origin) = self.origin.as_mut() {
+                *origin += duration;
+            }
+            self.next = 0;
+        }
+
+        let horizon = self.position() + self.lookahead;
+        self.timeline.entries[self.next..]
+            .iter_mut()
+            .take_while(|e| e.at <= horizon)
+            .try_for_each(|e| e.prebuild(autd.geometry()))?;
+
+        Ok(report)
+    }
+
+    /// Play the timeline until it finishes
+    ///
+    /// If `looping` is true, this returns only when an error occurs.
+    /// [AUTDError::ZeroPeriod] is returned if `looping` is true and the duration of the timeline is zero.
+    ///
+    /// # Arguments
+    ///
+    /// * `autd` - Controller to send the entries
+    ///
+    pub fn run<L: Link>(&mut self, autd: &mut Controller<L, T>) -> Result<SendReport, AUTDError> {
+        if self.looping && self.timeline.duration().is_zero() {
+            return Err(AUTDError::ZeroPeriod);
+        }
+        if autd.high_precision_timer {
+            self.run_with::<HighPrecisionWaiter, L>(autd)
+        } else {
+            self.run_with::<NormalWaiter, L>(autd)
+        }
+    }
+
+    fn run_with<W: Waiter, L: Link>(
+        &mut self,
+        autd: &mut Controller<L, T>,
+    ) -> Result<SendReport, AUTDError> {
+        self.play();
+        let mut report = SendReport::new(autd.geometry().num_devices());
+        loop {
+            report = report.merge(self.update(autd)?);
+            if self.is_finished() {
+                return Ok(report);
+            }
+            if let Some(deadline) = self.next_deadline() {
+                W::wait_until(deadline);
+            }
+        }
+    }
+
+    fn next_deadline(&self) -> Option<Instant> {
+        let origin = self.origin?;
+        let at = match self.timeline.entries.get(self.next) {
+            Some(e) => e.at,
+            None => self.timeline.duration(),
+        };
+        Some(origin + at)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use autd3_core::{silencer_config::SilencerConfig, CPUControlFlags};
+
+    use super::*;
+    use crate::{
+        modulation::Sine,
+        test_utils::{legacy_geometry, open},
+    };
+
+    fn silencer_steps(frames: &[TxDatagram]) -> Vec<u16> {
+        frames
+            .iter()
+            .filter(|tx| {
+                let flag = tx.header().cpu_flag;
+                !flag.contains(CPUControlFlags::MOD)
+                    && flag.contains(CPUControlFlags::CONFIG_SILENCER)
+            })
+            .map(|tx| tx.header().silencer_header().step)
+            .collect()
+    }
+
+    #[test]
+    fn run_sends_entries_in_order() {
+        let (mut autd, h) = open(legacy_geometry(1));
+        let timeline = Timeline::new()
+            .add(Duration::from_millis(4), SilencerConfig::new(30, 4096))
+            .add(Duration::ZERO, SilencerConfig::new(10, 4096))
+            .add(Duration::from_millis(4), SilencerConfig::new(40, 4096));
+        assert_eq!(timeline.duration(), Duration::from_millis(4));
+
+        let before = h.num_sent();
+        let mut player = TimelinePlayer::new(timeline);
+        let report = player.run(&mut autd).unwrap();
+        assert!(player.is_finished());
+        assert_eq!(report.frames(), 3);
+        assert_eq!(silencer_steps(&h.sent_after(before)), vec![10, 30, 40]);
+    }
+
+    #[test]
+    fn seek_replays_entries_at_latest_time() {
+        let (mut autd, h) = open(legacy_geometry(1));
+        let timeline = Timeline::new()
+            .add(Duration::ZERO, SilencerConfig::new(10, 4096))
+            .add(Duration::from_millis(10), SilencerConfig::new(20, 4096))
+            .add(Duration::from_millis(10), Sine::new(150))
+            .add(Duration::from_secs(10), SilencerConfig::new(30, 4096));
+        let mut player = TimelinePlayer::new(timeline);
+
+        let before = h.num_sent();
+        player.seek(Duration::from_millis(15));
+        player.play();
+        player.update(&mut autd).unwrap();
+
+        let sent = h.sent_after(before);
+        assert_eq!(silencer_steps(&sent), vec![20]);
+        assert!(sent
+            .iter()
+            .any(|tx| tx.header().cpu_flag.contains(CPUControlFlags::MOD)));
+        assert!(!player.is_finished());
+    }
+
+    #[test]
+    fn looping_zero_duration_is_rejected() {
+        let (mut autd, h) = open(legacy_geometry(1));
+        let timeline = Timeline::new().add(Duration::ZERO, SilencerConfig::new(10, 4096));
+        let mut player = TimelinePlayer::new(timeline);
+        player.looping = true;
+
+        let before = h.num_sent();
+        assert!(matches!(player.run(&mut autd), Err(AUTDError::ZeroPeriod)));
+        assert_eq!(h.num_sent(), before);
+    }
+}
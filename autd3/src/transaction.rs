@@ -0,0 +1,187 @@
+/*
+ * File: transaction.rs
+ * Project: src
+ * Created Date: 18/10/2026
+ * Author: Shun Suzuki
+ * -----
+ * Last Modified: 18/10/2026
+ * Modified By: Shun Suzuki (suzuki@hapis.k.u-tokyo.ac.jp)
+ * -----
+ * Copyright (c) 2026 Shun Suzuki. All rights reserved.
+ *
+ */
+
+use autd3_core::{
+    error::AUTDInternalError,
+    geometry::{Geometry, Transducer},
+    interface::{DatagramBody, DatagramHeader, Sendable},
+    TxDatagram,
+};
+
+/// Queue of headers sent one after another
+pub struct HeaderQueue<'a> {
+    items: Vec<Box<dyn DatagramHeader + 'a>>,
+    idx: usize,
+}
+
+impl<'a> HeaderQueue<'a> {
+    fn advance(&mut self) {
+        while self.idx < self.items.len() && self.items[self.idx].is_finished() {
+            self.idx += 1;
+        }
+    }
+}
+
+impl<'a> DatagramHeader for HeaderQueue<'a> {
+    fn init(&mut self) -> Result<(), AUTDInternalError> {
+        self.idx = 0;
+        self.items.iter_mut().try_for_each(|h| h.init())
+    }
+
+    fn pack(&mut self, msg_id: u8, tx: &mut TxDatagram) -> Result<(), AUTDInternalError> {
+        self.advance();
+        match self.items.get_mut(self.idx) {
+            Some(h) => h.pack(msg_id, tx),
+            None => {
+                autd3_core::null_header(msg_id, tx);
+                Ok(())
+            }
+        }
+    }
+
+    fn is_finished(&self) -> bool {
+        self.items[self.idx..].iter().all(|h| h.is_finished())
+    }
+}
+
+/// Queue of bodies sent one after another
+pub struct BodyQueue<'a, T: Transducer> {
+    items: Vec<Box<dyn DatagramBody<T> + 'a>>,
+    idx: usize,
+}
+
+impl<'a, T: Transducer> BodyQueue<'a, T> {
+    fn advance(&mut self) {
+        while self.idx < self.items.len() && self.items[self.idx].is_finished() {
+            self.idx += 1;
+        }
+    }
+}
+
+impl<'a, T: Transducer> DatagramBody<T> for BodyQueue<'a, T> {
+    fn init(&mut self) -> Result<(), AUTDInternalError> {
+        self.idx = 0;
+        self.items.iter_mut().try_for_each(|b| b.init())
+    }
+
+    fn pack(
+        &mut self,
+        geometry: &Geometry<T>,
+        tx: &mut TxDatagram,
+    ) -> Result<(), AUTDInternalError> {
+        self.advance();
+        match self.items.get_mut(self.idx) {
+            Some(b) => b.pack(geometry, tx),
+            None => {
+                autd3_core::null_body(tx);
+                Ok(())
+            }
+        }
+    }
+
+    fn is_finished(&self) -> bool {
+        self.items[self.idx..].iter().all(|b| b.is_finished())
+    }
+}
+
+/// Transaction to upload several headers and bodies at once
+///
+/// Headers (Modulation, SilencerConfig) and bodies (Gain, PointSTM, GainSTM, ModDelay) are sent in order of addition respectively,
+/// and a header and a body are packed into the same frame, so that the number of frames is minimized.
+///
+/// # Example
+///
+/// ```
+/// use autd3::prelude::*;
+/// use autd3_link_debug::Debug;
+///
+/// # fn main() -> Result<(), AUTDError> {
+/// let mut geometry = GeometryBuilder::new().legacy_mode().build();
+/// geometry.add_device(Vector3::zeros(), Vector3::zeros());
+///
+/// let mut autd = Controller::open(geometry, Debug::new())?;
+/// let center = autd.geometry().center();
+///
+/// let separated = autd.send(SilencerConfig::default())?.frames()
+///     + autd.send(Sine::new(150))?.frames()
+///     + autd.send(Focus::new(center))?.frames();
+///
+/// let tr = Transaction::new()
+///     .header(SilencerConfig::default())
+///     .header(Sine::new(150))
+///     .body(Focus::new(center));
+/// let report = autd.send(tr)?;
+///
+/// assert!(report.is_success());
+/// assert!(report.frames() < separated);
+///
+/// autd.close()?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct Transaction<'a, T: Transducer> {
+    headers: HeaderQueue<'a>,
+    bodies: BodyQueue<'a, T>,
+}
+
+impl<'a, T: Transducer> Transaction<'a, T> {
+    pub fn new() -> Self {
+        Self {
+            headers: HeaderQueue {
+                items: vec![],
+                idx: 0,
+            },
+            bodies: BodyQueue {
+                items: vec![],
+                idx: 0,
+            },
+        }
+    }
+
+    /// Add header to the transaction
+    ///
+    /// # Arguments
+    ///
+    /// * `header` - Header such as Modulation or SilencerConfig
+    ///
+    pub fn header<H: DatagramHeader + 'a>(mut self, header: H) -> Self {
+        self.headers.items.push(Box::new(header));
+        self
+    }
+
+    /// Add body to the transaction
+    ///
+    /// # Arguments
+    ///
+    /// * `body` - Body such as Gain, PointSTM, GainSTM or ModDelay
+    ///
+    pub fn body<B: DatagramBody<T> + 'a>(mut self, body: B) -> Self {
+        self.bodies.items.push(Box::new(body));
+        self
+    }
+}
+
+impl<'a, T: Transducer> Default for Transaction<'a, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, T: Transducer> Sendable<T> for Transaction<'a, T> {
+    type H = HeaderQueue<'a>;
+    type B = BodyQueue<'a, T>;
+
+    fn operation(self) -> (Self::H, Self::B) {
+        (self.headers, self.bodies)
+    }
+}
@@ -0,0 +1,123 @@
+/*
+ * File: validate.rs
+ * Project: src
+ * Created Date: 18/10/2026
+ * Author: Shun Suzuki
+ * -----
+ * Last Modified: 18/10/2026
+ * Modified By: Shun Suzuki (suzuki@hapis.k.u-tokyo.ac.jp)
+ * -----
+ * Copyright (c) 2026 Shun Suzuki. All rights reserved.
+ *
+ */
+
+use std::time::Duration;
+
+use autd3_core::{
+    geometry::{Geometry, Transducer},
+    interface::{DatagramBody, DatagramHeader, Sendable},
+    TxDatagram, MSG_BEGIN,
+};
+
+use crate::{engine::requires_sync, error::AUTDError};
+
+/// Result of packing data without sending
+#[derive(Debug)]
+pub struct Validation {
+    /// Errors found in packing. Packing of the header or the body stops at its first error.
+    pub errors: Vec<AUTDError>,
+    /// Number of frames to send the data
+    pub frames: usize,
+    /// Estimated time to send all frames, excluding waits for acknowledgements
+    pub transfer_time: Duration,
+    pub(crate) requires_sync: bool,
+}
+
+impl Validation {
+    pub fn is_ok(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+/// Pack data into scratch frames to find errors before sending
+///
+/// The transfer time is estimated with the minimum send interval.
+///
+/// # Arguments
+///
+/// * `s` - Header, body, or tuple of header and body
+/// * `geometry` - Geometry of the devices
+///
+/// ```
+/// use autd3::{autd3_core::modulation::Modulation, prelude::*};
+///
+/// # fn main() -> Result<(), AUTDError> {
+/// let mut geometry = GeometryBuilder::new().legacy_mode().build();
+/// geometry.add_device(Vector3::zeros(), Vector3::zeros());
+///
+/// let center = geometry.center();
+/// let res = validate((Sine::new(150), Focus::new(center)), &geometry);
+/// assert!(res.is_ok());
+/// assert!(res.frames > 0);
+///
+/// let mut m = Sine::new(150);
+/// *m.sampling_frequency_division() = 1;
+/// let mut stm = PointSTM::new();
+/// stm.add(center, 0)?;
+/// stm.add(center, 0)?;
+/// stm.set_sampling_freq_div(1);
+/// let res = validate((m, stm), &geometry);
+/// assert_eq!(res.errors.len(), 2);
+/// # Ok(())
+/// # }
+/// ```
+pub fn validate<T: Transducer, S: Sendable<T>>(s: S, geometry: &Geometry<T>) -> Validation {
+    validate_with_interval(
+        s,
+        geometry,
+        Duration::from_micros(autd3_core::EC_CYCLE_TIME_BASE_MICRO_SEC as u64),
+    )
+}
+
+pub(crate) fn validate_with_interval<T: Transducer, S: Sendable<T>>(
+    s: S,
+    geometry: &Geometry<T>,
+    interval: Duration,
+) -> Validation {
+    let (mut header, mut body) = s.operation();
+    let mut errors = vec![];
+    let mut header_ok = header.init().map_err(|e| errors.push(e.into())).is_ok();
+    let mut body_ok = body.init().map_err(|e| errors.push(e.into())).is_ok();
+
+    let mut tx = TxDatagram::new(geometry.num_devices());
+    let mut frames = 0;
+    let mut sync = false;
+    loop {
+        if !header_ok || header.is_finished() {
+            autd3_core::null_header(MSG_BEGIN, &mut tx);
+        } else if let Err(e) = header.pack(MSG_BEGIN, &mut tx) {
+            errors.push(e.into());
+            header_ok = false;
+            autd3_core::null_header(MSG_BEGIN, &mut tx);
+        }
+        if !body_ok || body.is_finished() {
+            autd3_core::null_body(&mut tx);
+        } else if let Err(e) = body.pack(geometry, &mut tx) {
+            errors.push(e.into());
+            body_ok = false;
+            autd3_core::null_body(&mut tx);
+        }
+        sync |= requires_sync(&tx);
+        frames += 1;
+        if (!header_ok || header.is_finished()) && (!body_ok || body.is_finished()) {
+            break;
+        }
+    }
+
+    Validation {
+        errors,
+        frames,
+        transfer_time: interval * frames as u32,
+        requires_sync: sync,
+    }
+}
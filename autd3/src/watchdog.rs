@@ -0,0 +1,223 @@
+/*
+ * File: watchdog.rs
+ * Project: src
+ * Created Date: 18/10/2026
+ * Author: Shun Suzuki
+ * -----
+ * Last Modified: 18/10/2026
+ * Modified By: Shun Suzuki (suzuki@hapis.k.u-tokyo.ac.jp)
+ * -----
+ * Copyright (c) 2026 Shun Suzuki. All rights reserved.
+ *
+ */
+
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    thread::JoinHandle,
+    time::{Duration, Instant},
+};
+
+#[cfg(feature = "async")]
+use autd3_core::link::AsyncLink;
+use autd3_core::{link::Link, TxDatagram};
+
+use crate::{controller::lock, msg_id::MsgIdAllocator};
+
+/// Maximum sleep of the watchdog, which bounds the delay to stop it
+const MAX_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Next action of the watchdog loop
+enum Poll {
+    Wait(Duration),
+    Expired,
+}
+
+/// State shared between the controller and the watchdog loop
+struct Shared {
+    running: AtomicBool,
+    last_fed: Mutex<Instant>,
+    armed: AtomicBool,
+    triggered: AtomicBool,
+    window: Duration,
+}
+
+impl Shared {
+    fn new(window: Duration) -> Self {
+        Self {
+            running: AtomicBool::new(true),
+            last_fed: Mutex::new(Instant::now()),
+            armed: AtomicBool::new(true),
+            triggered: AtomicBool::new(false),
+            window,
+        }
+    }
+
+    fn is_running(&self) -> bool {
+        self.running.load(Ordering::Acquire)
+    }
+
+    fn is_expired(&self) -> bool {
+        Instant::now() >= *lock(&self.last_fed) + self.window
+    }
+
+    fn poll(&self) -> Poll {
+        let deadline = *lock(&self.last_fed) + self.window;
+        let now = Instant::now();
+        if now < deadline {
+            Poll::Wait((deadline - now).min(MAX_POLL_INTERVAL))
+        } else if self.armed.load(Ordering::Acquire) {
+            Poll::Expired
+        } else {
+            Poll::Wait(MAX_POLL_INTERVAL)
+        }
+    }
+
+    fn trip(&self) {
+        self.armed.store(false, Ordering::Release);
+        self.triggered.store(true, Ordering::Release);
+    }
+}
+
+enum Runner {
+    Thread(Option<JoinHandle<()>>),
+    #[cfg(feature = "async")]
+    Task(tokio::task::JoinHandle<()>),
+}
+
+/// Background thread or task which sends stop frames when the controller is not fed within the window
+pub(crate) struct Watchdog {
+    shared: Arc<Shared>,
+    frames: Vec<TxDatagram>,
+    runner: Runner,
+}
+
+impl Watchdog {
+    /// Start the watchdog on a background thread
+    ///
+    /// # Arguments
+    ///
+    /// * `link` - Link shared with the controller
+    /// * `msg_id` - Message ID allocator shared with the controller
+    /// * `frames` - Packed frames to stop outputting, whose message IDs are overwritten on sending
+    /// * `window` - Time allowed without feeding
+    ///
+    pub fn start<L: Link + 'static>(
+        link: Arc<Mutex<L>>,
+        msg_id: Arc<MsgIdAllocator>,
+        frames: Vec<TxDatagram>,
+        window: Duration,
+    ) -> Self {
+        let shared = Arc::new(Shared::new(window));
+        let th = {
+            let shared = shared.clone();
+            let mut frames = frames.clone();
+            std::thread::spawn(move || {
+                while shared.is_running() {
+                    match shared.poll() {
+                        Poll::Wait(wait) => std::thread::sleep(wait),
+                        Poll::Expired => {
+                            let mut link = lock(&link);
+                            // the controller feeds before locking the link, so check again to not override a new frame
+                            if shared.is_expired() && link.is_open() {
+                                frames.iter_mut().for_each(|tx| {
+                                    tx.header_mut().msg_id = msg_id.next();
+                                    let _ = link.send(tx);
+                                });
+                                shared.trip();
+                            }
+                            drop(link);
+                            std::thread::sleep(MAX_POLL_INTERVAL);
+                        }
+                    }
+                }
+            })
+        };
+        Self {
+            shared,
+            frames,
+            runner: Runner::Thread(Some(th)),
+        }
+    }
+
+    /// Start the watchdog as a task on the current tokio runtime
+    ///
+    /// The arguments are the same as [Watchdog::start].
+    #[cfg(feature = "async")]
+    pub fn start_async<L: AsyncLink + 'static>(
+        link: Arc<tokio::sync::Mutex<L>>,
+        msg_id: Arc<MsgIdAllocator>,
+        frames: Vec<TxDatagram>,
+        window: Duration,
+    ) -> Self {
+        let shared = Arc::new(Shared::new(window));
+        let task = {
+            let shared = shared.clone();
+            let mut frames = frames.clone();
+            tokio::spawn(async move {
+                while shared.is_running() {
+                    match shared.poll() {
+                        Poll::Wait(wait) => tokio::time::sleep(wait).await,
+                        Poll::Expired => {
+                            let mut link = link.lock().await;
+                            if shared.is_expired() && link.is_open() {
+                                for tx in frames.iter_mut() {
+                                    tx.header_mut().msg_id = msg_id.next();
+                                    let _ = link.send(tx).await;
+                                }
+                                shared.trip();
+                            }
+                            drop(link);
+                            tokio::time::sleep(MAX_POLL_INTERVAL).await;
+                        }
+                    }
+                }
+            })
+        };
+        Self {
+            shared,
+            frames,
+            runner: Runner::Task(task),
+        }
+    }
+
+    /// Reset the deadline and rearm the watchdog
+    ///
+    /// This does not clear the trip, which is cleared only by [Watchdog::take_triggered].
+    pub fn feed(&self) {
+        *lock(&self.shared.last_fed) = Instant::now();
+        self.shared.armed.store(true, Ordering::Release);
+    }
+
+    /// Return true if the watchdog has sent the stop frames and the trip has not been taken yet
+    pub fn is_triggered(&self) -> bool {
+        self.shared.triggered.load(Ordering::Acquire)
+    }
+
+    /// Return true and clear the trip if the watchdog has sent the stop frames
+    pub fn take_triggered(&self) -> bool {
+        self.shared.triggered.swap(false, Ordering::AcqRel)
+    }
+
+    /// Stop frames sent on a trip
+    pub fn frames(&self) -> &[TxDatagram] {
+        &self.frames
+    }
+}
+
+impl Drop for Watchdog {
+    fn drop(&mut self) {
+        self.shared.running.store(false, Ordering::Release);
+        match &mut self.runner {
+            Runner::Thread(th) => {
+                if let Some(th) = th.take() {
+                    let _ = th.join();
+                }
+            }
+            #[cfg(feature = "async")]
+            Runner::Task(task) => task.abort(),
+        }
+    }
+}